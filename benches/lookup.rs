@@ -0,0 +1,994 @@
+//! Compares the default `match` against the `trie` and `phf` lookup
+//! strategies for a reverse function over 900 string associations.
+//!
+//! Run with `cargo bench --bench lookup`.
+
+use enum_assoc::Assoc;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+macro_rules! keyword_enum {
+    ($name:ident, [$($lookup:tt)*], $($variant:ident = $keyword:literal,)*) => {
+        #[derive(Assoc, Clone, Copy, Debug, PartialEq)]
+        #[assoc_builtin(index)]
+        #[func(pub fn from_keyword(s: &str) -> Option<Self> $($lookup)*)]
+        pub enum $name {
+            $(
+                #[assoc(from_keyword = $keyword)]
+                $variant,
+            )*
+        }
+    };
+}
+
+macro_rules! keywords {
+    ($($variant:ident = $keyword:literal,)*) => {
+        keyword_enum!(Match, [], $($variant = $keyword,)*);
+        keyword_enum!(Trie, [, lookup = trie], $($variant = $keyword,)*);
+        keyword_enum!(Phf, [, lookup = phf], $($variant = $keyword,)*);
+
+        const KEYWORDS: &[&str] = &[$($keyword),*];
+    };
+}
+
+keywords! {
+    Abacsara = "abacsara",
+    Abanst = "abanst",
+    Abar = "abar",
+    Abasva = "abasva",
+    Abcechwaac = "abcechwaac",
+    Abcefimima = "abcefimima",
+    Abch = "abch",
+    Abcosene = "abcosene",
+    Abenis = "abenis",
+    Abfitest = "abfitest",
+    Abfote = "abfote",
+    Abloarloan = "abloarloan",
+    Abmere = "abmere",
+    Abno = "abno",
+    Abofchnobe = "abofchnobe",
+    Abpoac = "abpoac",
+    Absi = "absi",
+    Abtiat = "abtiat",
+    Abtixosi = "abtixosi",
+    Abunon = "abunon",
+    Abva = "abva",
+    Abxoac = "abxoac",
+    Acacme = "acacme",
+    Acargras = "acargras",
+    Acasce = "acasce",
+    Acch = "acch",
+    Acdine = "acdine",
+    Acfitr = "acfitr",
+    Acis = "acis",
+    Acisofrais = "acisofrais",
+    Acmadefi = "acmadefi",
+    Acne = "acne",
+    Acno = "acno",
+    Acnoor = "acnoor",
+    Aconansidi = "aconansidi",
+    Acorbeatlo = "acorbeatlo",
+    Acorcoas = "acorcoas",
+    Acsionsepa = "acsionsepa",
+    Acte = "acte",
+    Alar = "alar",
+    Alex = "alex",
+    Allore = "allore",
+    Alme = "alme",
+    Almidi = "almidi",
+    Alnoalbe = "alnoalbe",
+    Alnowipa = "alnowipa",
+    Alonmeteac = "alonmeteac",
+    Alranowewe = "alranowewe",
+    Alsa = "alsa",
+    Althunun = "althunun",
+    Aluntrpo = "aluntrpo",
+    Alwa = "alwa",
+    Alwe = "alwe",
+    Anacen = "anacen",
+    Anancosese = "anancosese",
+    Anasrawast = "anasrawast",
+    Andeis = "andeis",
+    Andisialpo = "andisialpo",
+    Angrmate = "angrmate",
+    Aninan = "aninan",
+    Aninchanwa = "aninchanwa",
+    Anofchfi = "anofchfi",
+    Anontracab = "anontracab",
+    Anrean = "anrean",
+    Anreunpa = "anreunpa",
+    Ansainch = "ansainch",
+    Anstst = "anstst",
+    Antr = "antr",
+    Anvabeth = "anvabeth",
+    Anvadino = "anvadino",
+    Anwastfore = "anwastfore",
+    Anwewide = "anwewide",
+    Anxogr = "anxogr",
+    Arabsa = "arabsa",
+    Aracchex = "aracchex",
+    Ararandi = "ararandi",
+    Ararfoasst = "ararfoasst",
+    Aratofsiar = "aratofsiar",
+    Arch = "arch",
+    Arfiexab = "arfiexab",
+    Arfotipoin = "arfotipoin",
+    Arnorarame = "arnorarame",
+    Arordeva = "arordeva",
+    Arpati = "arpati",
+    Arpo = "arpo",
+    Arsanoanel = "arsanoanel",
+    Arst = "arst",
+    Artrtesise = "artrtesise",
+    Arva = "arva",
+    Arwaroiswa = "arwaroiswa",
+    Arwema = "arwema",
+    Arweofxoar = "arweofxoar",
+    Arxo = "arxo",
+    Arxowaof = "arxowaof",
+    Asacfo = "asacfo",
+    Ascowi = "ascowi",
+    Aseldiin = "aseldiin",
+    Asinaltr = "asinaltr",
+    Asinfowath = "asinfowath",
+    Asnonoco = "asnonoco",
+    Assaarxogr = "assaarxogr",
+    Assifi = "assifi",
+    Asthnoardi = "asthnoardi",
+    Astr = "astr",
+    Aswabera = "aswabera",
+    Aswefi = "aswefi",
+    Aswewior = "aswewior",
+    Asxorero = "asxorero",
+    Atabwemino = "atabwemino",
+    Atac = "atac",
+    Atalfoalwi = "atalfoalwi",
+    Atalof = "atalof",
+    Atas = "atas",
+    Atde = "atde",
+    Atdetafo = "atdetafo",
+    Atensema = "atensema",
+    Atexlo = "atexlo",
+    Atexth = "atexth",
+    Atis = "atis",
+    Atre = "atre",
+    Atsasefi = "atsasefi",
+    Atsion = "atsion",
+    Atst = "atst",
+    Atstroinar = "atstroinar",
+    Atthdi = "atthdi",
+    Atthun = "atthun",
+    Atva = "atva",
+    Atweiswigr = "atweiswigr",
+    Bealra = "bealra",
+    Bechse = "bechse",
+    Bede = "bede",
+    Bedeunfo = "bedeunfo",
+    Beelsipa = "beelsipa",
+    Befilo = "befilo",
+    Beinwava = "beinwava",
+    Beonmi = "beonmi",
+    Beorchtith = "beorchtith",
+    Bepore = "bepore",
+    Berewe = "berewe",
+    Betaon = "betaon",
+    Bethth = "bethth",
+    Beti = "beti",
+    Bexo = "bexo",
+    Bexoor = "bexoor",
+    Ceaborthma = "ceaborthma",
+    Cear = "cear",
+    Cearasth = "cearasth",
+    Ceartilofi = "ceartilofi",
+    Ceat = "ceat",
+    Cebechin = "cebechin",
+    Cece = "cece",
+    Cecoen = "cecoen",
+    Cedivama = "cedivama",
+    Cefoasfo = "cefoasfo",
+    Cegrabtais = "cegrabtais",
+    Ceinwiab = "ceinwiab",
+    Celova = "celova",
+    Cemiasexlo = "cemiasexlo",
+    Cepoonunfo = "cepoonunfo",
+    Cest = "cest",
+    Cestwera = "cestwera",
+    Cetesiisof = "cetesiisof",
+    Cethst = "cethst",
+    Cetiextr = "cetiextr",
+    Cewaroun = "cewaroun",
+    Cewegr = "cewegr",
+    Cexoun = "cexoun",
+    Chab = "chab",
+    Chanen = "chanen",
+    Chanpoor = "chanpoor",
+    Chco = "chco",
+    Chcoinfo = "chcoinfo",
+    Chcoxofibe = "chcoxofibe",
+    Chenwe = "chenwe",
+    Chexte = "chexte",
+    Chfi = "chfi",
+    Chgr = "chgr",
+    Chgracalfi = "chgracalfi",
+    Chgrlofi = "chgrlofi",
+    Chgrxopa = "chgrxopa",
+    Chpoloextr = "chpoloextr",
+    Chroorbexo = "chroorbexo",
+    Chsi = "chsi",
+    Chthansa = "chthansa",
+    Chthgrfo = "chthgrfo",
+    Chtrasof = "chtrasof",
+    Coalmeel = "coalmeel",
+    Coceabsa = "coceabsa",
+    Coch = "coch",
+    Cococo = "cococo",
+    Codiatre = "codiatre",
+    Coelexra = "coelexra",
+    Coelgrpo = "coelgrpo",
+    Coelmano = "coelmano",
+    Coenfositi = "coenfositi",
+    Coinwimi = "coinwimi",
+    Cololo = "cololo",
+    Comeinasva = "comeinasva",
+    Comiwe = "comiwe",
+    Conestoren = "conestoren",
+    Coonfomadi = "coonfomadi",
+    Copo = "copo",
+    Coroweinse = "coroweinse",
+    Coseen = "coseen",
+    Cosice = "cosice",
+    Cosivasa = "cosivasa",
+    Costistiof = "costistiof",
+    Cotanetein = "cotanetein",
+    Counasance = "counasance",
+    Cowa = "cowa",
+    Cowetatepo = "cowetatepo",
+    Cowi = "cowi",
+    Cowitais = "cowitais",
+    Coxo = "coxo",
+    Coxoun = "coxoun",
+    Deab = "deab",
+    Deac = "deac",
+    Deasce = "deasce",
+    Debe = "debe",
+    Deceno = "deceno",
+    Dech = "dech",
+    Decounmane = "decounmane",
+    Degrex = "degrex",
+    Deinta = "deinta",
+    Delosi = "delosi",
+    Demate = "demate",
+    Deofne = "deofne",
+    Derateac = "derateac",
+    Deremaacfo = "deremaacfo",
+    Dese = "dese",
+    Destlofi = "destlofi",
+    Detemist = "detemist",
+    Detrra = "detrra",
+    Diabextawi = "diabextawi",
+    Diabtawe = "diabtawe",
+    Diar = "diar",
+    Diasanor = "diasanor",
+    Dibestis = "dibestis",
+    Dice = "dice",
+    Didi = "didi",
+    Diexmasa = "diexmasa",
+    Diinas = "diinas",
+    Diis = "diis",
+    Dilotrarme = "dilotrarme",
+    Dimi = "dimi",
+    Dinoantr = "dinoantr",
+    Diracest = "diracest",
+    Disano = "disano",
+    Disiabwiwi = "disiabwiwi",
+    Disiva = "disiva",
+    Ditexo = "ditexo",
+    Dithanasne = "dithanasne",
+    Dithchen = "dithchen",
+    Ditiva = "ditiva",
+    Elal = "elal",
+    Elcegr = "elcegr",
+    Elch = "elch",
+    Eldi = "eldi",
+    Eldiwion = "eldiwion",
+    Elmearco = "elmearco",
+    Elmeenunre = "elmeenunre",
+    Elrasafiof = "elrasafiof",
+    Elsaabarra = "elsaabarra",
+    Else = "else",
+    Elstch = "elstch",
+    Elth = "elth",
+    Elthdefidi = "elthdefidi",
+    Eltiab = "eltiab",
+    Elvatava = "elvatava",
+    Elwast = "elwast",
+    Elwatrlo = "elwatrlo",
+    Elwe = "elwe",
+    Elwiroaran = "elwiroaran",
+    Enal = "enal",
+    Enatvaen = "enatvaen",
+    Ench = "ench",
+    Encoorin = "encoorin",
+    Enfifiab = "enfifiab",
+    Engrloas = "engrloas",
+    Enoftedi = "enoftedi",
+    Enorneasis = "enorneasis",
+    Enortr = "enortr",
+    Enraar = "enraar",
+    Enstnounar = "enstnounar",
+    Ente = "ente",
+    Entiwiorbe = "entiwiorbe",
+    Exar = "exar",
+    Exasfoma = "exasfoma",
+    Exastaabst = "exastaabst",
+    Exatinacwa = "exatinacwa",
+    Exdiasno = "exdiasno",
+    Exenmeas = "exenmeas",
+    Exenti = "exenti",
+    Exfiofce = "exfiofce",
+    Exfiweal = "exfiweal",
+    Exis = "exis",
+    Exisneastr = "exisneastr",
+    Exmefi = "exmefi",
+    Exmi = "exmi",
+    Exmien = "exmien",
+    Exof = "exof",
+    Expaco = "expaco",
+    Expome = "expome",
+    Exrerowe = "exrerowe",
+    Exsa = "exsa",
+    Exsael = "exsael",
+    Exxoofwiel = "exxoofwiel",
+    Fiacdiunxo = "fiacdiunxo",
+    Fiacsaarce = "fiacsaarce",
+    Fialtetrme = "fialtetrme",
+    Fias = "fias",
+    Fiasro = "fiasro",
+    Fibearma = "fibearma",
+    Fidixo = "fidixo",
+    Fifosamach = "fifosamach",
+    Fifowaroin = "fifowaroin",
+    Filobeteas = "filobeteas",
+    Fiofpava = "fiofpava",
+    Fipoanro = "fipoanro",
+    Firaceor = "firaceor",
+    Fireab = "fireab",
+    Fisaanal = "fisaanal",
+    Fiun = "fiun",
+    Fiwean = "fiwean",
+    Fiwior = "fiwior",
+    Fixo = "fixo",
+    Foacbedero = "foacbedero",
+    Foacwisipa = "foacwisipa",
+    Foan = "foan",
+    Foarenta = "foarenta",
+    Foatsitr = "foatsitr",
+    Fodisedepa = "fodisedepa",
+    Foel = "foel",
+    Foenno = "foenno",
+    Fofiab = "fofiab",
+    Fogronro = "fogronro",
+    Fois = "fois",
+    Fomare = "fomare",
+    Fomecogrmi = "fomecogrmi",
+    Fomeon = "fomeon",
+    Fomime = "fomime",
+    Fooraben = "fooraben",
+    Foorgrwe = "foorgrwe",
+    Foposiison = "foposiison",
+    Fosiwa = "fosiwa",
+    Fotrchsi = "fotrchsi",
+    Gralfine = "gralfine",
+    Grasorwe = "grasorwe",
+    Grat = "grat",
+    Grce = "grce",
+    Grceatwidi = "grceatwidi",
+    Grdi = "grdi",
+    Grdias = "grdias",
+    Grdion = "grdion",
+    Grfistmiex = "grfistmiex",
+    Gris = "gris",
+    Grlotrarun = "grlotrarun",
+    Grma = "grma",
+    Grmionar = "grmionar",
+    Grnotiacte = "grnotiacte",
+    Groffovaro = "groffovaro",
+    Grorel = "grorel",
+    Grortimi = "grortimi",
+    Grrasalome = "grrasalome",
+    Grsafiexof = "grsafiexof",
+    Grteti = "grteti",
+    Grth = "grth",
+    Grthfimiwe = "grthfimiwe",
+    Grvaan = "grvaan",
+    Grweun = "grweun",
+    Grxoon = "grxoon",
+    Grxoretrth = "grxoretrth",
+    Inasonatmi = "inasonatmi",
+    Indiacta = "indiacta",
+    Inen = "inen",
+    Inex = "inex",
+    Infifogr = "infifogr",
+    Ingrunun = "ingrunun",
+    Ininme = "ininme",
+    Inis = "inis",
+    Inloloan = "inloloan",
+    Inmebesa = "inmebesa",
+    Inoncodi = "inoncodi",
+    Inorwa = "inorwa",
+    Inrobeacre = "inrobeacre",
+    Inroro = "inroro",
+    Insi = "insi",
+    Intaentast = "intaentast",
+    Inun = "inun",
+    Isarwemi = "isarwemi",
+    Isat = "isat",
+    Isbean = "isbean",
+    Isel = "isel",
+    Isfiofab = "isfiofab",
+    Islopa = "islopa",
+    Isro = "isro",
+    Issetithta = "issetithta",
+    Isseva = "isseva",
+    Isteno = "isteno",
+    Isvawe = "isvawe",
+    Loac = "loac",
+    Loarxoel = "loarxoel",
+    Loaswasi = "loaswasi",
+    Lobe = "lobe",
+    Loberewe = "loberewe",
+    Locostorwa = "locostorwa",
+    Lofi = "lofi",
+    Lomaxo = "lomaxo",
+    Lomexoce = "lomexoce",
+    Lomitrexme = "lomitrexme",
+    Lone = "lone",
+    Lonetialre = "lonetialre",
+    Lore = "lore",
+    Lota = "lota",
+    Lotesath = "lotesath",
+    Lotrin = "lotrin",
+    Maanrarein = "maanrarein",
+    Mabe = "mabe",
+    Macodeof = "macodeof",
+    Maex = "maex",
+    Maexreexun = "maexreexun",
+    Mainmeon = "mainmeon",
+    Malotico = "malotico",
+    Mamefowadi = "mamefowadi",
+    Mano = "mano",
+    Mapoce = "mapoce",
+    Maretr = "maretr",
+    Mathva = "mathva",
+    Matimeor = "matimeor",
+    Maxoararof = "maxoararof",
+    Meabraneas = "meabraneas",
+    Mealgr = "mealgr",
+    Mebe = "mebe",
+    Mebepotibe = "mebepotibe",
+    Mechse = "mechse",
+    Meel = "meel",
+    Meeltiabor = "meeltiabor",
+    Mein = "mein",
+    Memiente = "memiente",
+    Meor = "meor",
+    Mepopaex = "mepopaex",
+    Mera = "mera",
+    Mere = "mere",
+    Mesitire = "mesitire",
+    Metacete = "metacete",
+    Meteat = "meteat",
+    Meun = "meun",
+    Meva = "meva",
+    Mexo = "mexo",
+    Miab = "miab",
+    Miacanro = "miacanro",
+    Mias = "mias",
+    Miasacro = "miasacro",
+    Micefiwi = "micefiwi",
+    Midece = "midece",
+    Midien = "midien",
+    Miel = "miel",
+    Mienbene = "mienbene",
+    Miin = "miin",
+    Mimisi = "mimisi",
+    Mior = "mior",
+    Mipaabis = "mipaabis",
+    Mipochpoab = "mipochpoab",
+    Mirepome = "mirepome",
+    Misaofch = "misaofch",
+    Misepa = "misepa",
+    Mist = "mist",
+    Miteacra = "miteacra",
+    Miweatsa = "miweatsa",
+    Miwiinbero = "miwiinbero",
+    Neactrstat = "neactrstat",
+    Neas = "neas",
+    Nechwifi = "nechwifi",
+    Neel = "neel",
+    Neelromi = "neelromi",
+    Neex = "neex",
+    Neextadi = "neextadi",
+    Negrco = "negrco",
+    Nenewa = "nenewa",
+    Nepane = "nepane",
+    Neroor = "neroor",
+    Nesa = "nesa",
+    Nesaceis = "nesaceis",
+    Nese = "nese",
+    Neta = "neta",
+    Neth = "neth",
+    Neunfi = "neunfi",
+    Newimeon = "newimeon",
+    Nexoas = "nexoas",
+    Noaldivaxo = "noaldivaxo",
+    Noatxoof = "noatxoof",
+    Noenbeteof = "noenbeteof",
+    Nomipa = "nomipa",
+    Nonoasacfo = "nonoasacfo",
+    Noofmipost = "noofmipost",
+    Nora = "nora",
+    Nosacearva = "nosacearva",
+    Nosais = "nosais",
+    Nosasi = "nosasi",
+    Nothelmiti = "nothelmiti",
+    Notianelme = "notianelme",
+    Notrondiro = "notrondiro",
+    Nounnowi = "nounnowi",
+    Nowereon = "nowereon",
+    Nowiismiin = "nowiismiin",
+    Nowipo = "nowipo",
+    Noxomaac = "noxomaac",
+    Ofanalrean = "ofanalrean",
+    Ofantaoflo = "ofantaoflo",
+    Ofarwa = "ofarwa",
+    Ofcoor = "ofcoor",
+    Ofex = "ofex",
+    Ofisorro = "ofisorro",
+    Ofra = "ofra",
+    Ofrorafo = "ofrorafo",
+    Ofthwipo = "ofthwipo",
+    Ofun = "ofun",
+    Ofwaanse = "ofwaanse",
+    Ofwidi = "ofwidi",
+    Ofwisaatro = "ofwisaatro",
+    Ofxoreth = "ofxoreth",
+    Onacof = "onacof",
+    Onantipo = "onantipo",
+    Onasfoweun = "onasfoweun",
+    Onatweel = "onatweel",
+    Onbefi = "onbefi",
+    Once = "once",
+    Onchor = "onchor",
+    Onfiro = "onfiro",
+    Onfova = "onfova",
+    Oninab = "oninab",
+    Onisan = "onisan",
+    Onma = "onma",
+    Onmata = "onmata",
+    Onroaslo = "onroaslo",
+    Onstmaon = "onstmaon",
+    Onte = "onte",
+    Onthrepa = "onthrepa",
+    Ontrmith = "ontrmith",
+    Onwaab = "onwaab",
+    Onweat = "onweat",
+    Onwevapo = "onwevapo",
+    Onwi = "onwi",
+    Onxofialar = "onxofialar",
+    Orac = "orac",
+    Oral = "oral",
+    Oranensi = "oranensi",
+    Oras = "oras",
+    Orasbe = "orasbe",
+    Orchtewe = "orchtewe",
+    Orfi = "orfi",
+    Orgrstmalo = "orgrstmalo",
+    Orin = "orin",
+    Orofmidine = "orofmidine",
+    Orpolo = "orpolo",
+    Orthwiatmi = "orthwiatmi",
+    Ortr = "ortr",
+    Orvaex = "orvaex",
+    Orweexti = "orweexti",
+    Orwewirepa = "orwewirepa",
+    Paasmaloon = "paasmaloon",
+    Paco = "paco",
+    Paloch = "paloch",
+    Pame = "pame",
+    Panorefoco = "panorefoco",
+    Parocoma = "parocoma",
+    Pasapasa = "pasapasa",
+    Pasi = "pasi",
+    Past = "past",
+    Path = "path",
+    Patrmemi = "patrmemi",
+    Podeexpa = "podeexpa",
+    Poensitepa = "poensitepa",
+    Pogrcoal = "pogrcoal",
+    Polo = "polo",
+    Poma = "poma",
+    Poofco = "poofco",
+    Popaloun = "popaloun",
+    Poro = "poro",
+    Porodireco = "porodireco",
+    Postrefi = "postrefi",
+    Poti = "poti",
+    Powath = "powath",
+    Powi = "powi",
+    Poxogrneof = "poxogrneof",
+    Raalsati = "raalsati",
+    Raarti = "raarti",
+    Raastr = "raastr",
+    Raatco = "raatco",
+    Raceatwewi = "raceatwewi",
+    Rafoel = "rafoel",
+    Raindeaban = "raindeaban",
+    Rais = "rais",
+    Raisabro = "raisabro",
+    Ramadepaac = "ramadepaac",
+    Raorof = "raorof",
+    Rapo = "rapo",
+    Raraab = "raraab",
+    Rare = "rare",
+    Rarerosete = "rarerosete",
+    Rase = "rase",
+    Rasigrva = "rasigrva",
+    Ratadean = "ratadean",
+    Ratiat = "ratiat",
+    Rawifomaar = "rawifomaar",
+    Raxotiti = "raxotiti",
+    Reab = "reab",
+    Reabpo = "reabpo",
+    Reacababwa = "reacababwa",
+    Realno = "realno",
+    Rear = "rear",
+    Reatabne = "reatabne",
+    Reatmami = "reatmami",
+    Reatwionen = "reatwionen",
+    Rebe = "rebe",
+    Receexteab = "receexteab",
+    Refi = "refi",
+    Refosachsa = "refosachsa",
+    Reiscoin = "reiscoin",
+    Relopach = "relopach",
+    Relorode = "relorode",
+    Renemipa = "renemipa",
+    Reor = "reor",
+    Reseraen = "reseraen",
+    Resisitiwa = "resisitiwa",
+    Restal = "restal",
+    Reun = "reun",
+    Reunnexo = "reunnexo",
+    Rewe = "rewe",
+    Rewiweanon = "rewiweanon",
+    Roatceas = "roatceas",
+    Rochab = "rochab",
+    Roexwi = "roexwi",
+    Rofonegr = "rofonegr",
+    Rogr = "rogr",
+    Roofac = "roofac",
+    Roofofstgr = "roofofstgr",
+    Roonen = "roonen",
+    Ropowace = "ropowace",
+    Roremeor = "roremeor",
+    Roroaldema = "roroaldema",
+    Rose = "rose",
+    Rost = "rost",
+    Rostatun = "rostatun",
+    Rova = "rova",
+    Roweelis = "roweelis",
+    Saacun = "saacun",
+    Saanorfi = "saanorfi",
+    Saarwigrbe = "saarwigrbe",
+    Saattr = "saattr",
+    Sadicosa = "sadicosa",
+    Saen = "saen",
+    Saensaloxo = "saensaloxo",
+    Samipost = "samipost",
+    Sapa = "sapa",
+    Saromidede = "saromidede",
+    Satava = "satava",
+    Sathramipa = "sathramipa",
+    Satr = "satr",
+    Savathmi = "savathmi",
+    Seacsepone = "seacsepone",
+    Sedecearme = "sedecearme",
+    Sefo = "sefo",
+    Sefomain = "sefomain",
+    Seistror = "seistror",
+    Semami = "semami",
+    Seofenno = "seofenno",
+    Seofne = "seofne",
+    Sereneth = "sereneth",
+    Seth = "seth",
+    Siarco = "siarco",
+    Siarre = "siarre",
+    Sibe = "sibe",
+    Sidirealin = "sidirealin",
+    Sienre = "sienre",
+    Siensire = "siensire",
+    Sifowiabra = "sifowiabra",
+    Sinecedi = "sinecedi",
+    Sinowasexo = "sinowasexo",
+    Sirata = "sirata",
+    Sisano = "sisano",
+    Sitiexsede = "sitiexsede",
+    Sixosathof = "sixosathof",
+    Stabrowi = "stabrowi",
+    Stbeun = "stbeun",
+    Stchinof = "stchinof",
+    Stench = "stench",
+    Stnosttr = "stnosttr",
+    Storatmedi = "storatmedi",
+    Stpabe = "stpabe",
+    Straabsion = "straabsion",
+    Stravatr = "stravatr",
+    Sttathsesi = "sttathsesi",
+    Sttirein = "sttirein",
+    Sttr = "sttr",
+    Stva = "stva",
+    Stvalo = "stvalo",
+    Taabta = "taabta",
+    Tacemefi = "tacemefi",
+    Taderoinwe = "taderoinwe",
+    Tafoac = "tafoac",
+    Taisaswest = "taisaswest",
+    Taofsine = "taofsine",
+    Tapa = "tapa",
+    Taradere = "taradere",
+    Tare = "tare",
+    Tarerobeex = "tarerobeex",
+    Tasa = "tasa",
+    Tasaason = "tasaason",
+    Tasaceinro = "tasaceinro",
+    Tasiwi = "tasiwi",
+    Tawaditeof = "tawaditeof",
+    Tawaloan = "tawaloan",
+    Tawi = "tawi",
+    Taxochneex = "taxochneex",
+    Teacreacva = "teacreacva",
+    Tealin = "tealin",
+    Techonst = "techonst",
+    Teelcono = "teelcono",
+    Teello = "teello",
+    Teenroseat = "teenroseat",
+    Tefibese = "tefibese",
+    Temachelal = "temachelal",
+    Temaisindi = "temaisindi",
+    Temapaex = "temapaex",
+    Teno = "teno",
+    Teon = "teon",
+    Tera = "tera",
+    Tesa = "tesa",
+    Teth = "teth",
+    Teun = "teun",
+    Tevaof = "tevaof",
+    Tewe = "tewe",
+    Tewearne = "tewearne",
+    Teweva = "teweva",
+    Thalst = "thalst",
+    Thanrasico = "thanrasico",
+    Thbeacneel = "thbeacneel",
+    Thchseel = "thchseel",
+    Then = "then",
+    Thenabgrco = "thenabgrco",
+    Thexcelo = "thexcelo",
+    Thmeceta = "thmeceta",
+    Thongrta = "thongrta",
+    Thpafodi = "thpafodi",
+    Thsa = "thsa",
+    Thta = "thta",
+    Thti = "thti",
+    Tialwa = "tialwa",
+    Tianpoch = "tianpoch",
+    Tiasmanova = "tiasmanova",
+    Ticedipova = "ticedipova",
+    Tico = "tico",
+    Tifoxo = "tifoxo",
+    Tigronva = "tigronva",
+    Tiin = "tiin",
+    Time = "time",
+    Tinegr = "tinegr",
+    Tinose = "tinose",
+    Tiorat = "tiorat",
+    Tipadi = "tipadi",
+    Tira = "tira",
+    Tire = "tire",
+    Tisaxoar = "tisaxoar",
+    Tistelisen = "tistelisen",
+    Titetati = "titetati",
+    Tithseac = "tithseac",
+    Trchis = "trchis",
+    Trdeat = "trdeat",
+    Trfiorondi = "trfiorondi",
+    Trisasnowe = "trisasnowe",
+    Trlowi = "trlowi",
+    Trne = "trne",
+    Trra = "trra",
+    Trrexo = "trrexo",
+    Trstwanoin = "trstwanoin",
+    Trti = "trti",
+    Trunde = "trunde",
+    Trvadita = "trvadita",
+    Trweat = "trweat",
+    Unacfono = "unacfono",
+    Unanassa = "unanassa",
+    Unar = "unar",
+    Unarpaabde = "unarpaabde",
+    Unatananxo = "unatananxo",
+    Unatdear = "unatdear",
+    Undexo = "undexo",
+    Unenpa = "unenpa",
+    Unexpoac = "unexpoac",
+    Unfotitawa = "unfotitawa",
+    Unmera = "unmera",
+    Unmi = "unmi",
+    Unwatadias = "unwatadias",
+    Unweelanme = "unweelanme",
+    Unwion = "unwion",
+    Unxosaacor = "unxosaacor",
+    Vaacofin = "vaacofin",
+    Vaal = "vaal",
+    Vaat = "vaat",
+    Vabepoasno = "vabepoasno",
+    Vabest = "vabest",
+    Vacegrware = "vacegrware",
+    Vadebepo = "vadebepo",
+    Vafiorxone = "vafiorxone",
+    Vafoonfise = "vafoonfise",
+    Vainwe = "vainwe",
+    Valoselo = "valoselo",
+    Vameantegr = "vameantegr",
+    Vamibepost = "vamibepost",
+    Vaneexmi = "vaneexmi",
+    Vaof = "vaof",
+    Vapoon = "vapoon",
+    Vast = "vast",
+    Vata = "vata",
+    Vathrama = "vathrama",
+    Vatr = "vatr",
+    Vawachma = "vawachma",
+    Vaxoxo = "vaxoxo",
+    Waabra = "waabra",
+    Waalsa = "waalsa",
+    Waatno = "waatno",
+    Waatofmeun = "waatofmeun",
+    Waatofro = "waatofro",
+    Wabede = "wabede",
+    Wabesiisgr = "wabesiisgr",
+    Wacoroofth = "wacoroofth",
+    Wadepo = "wadepo",
+    Waelrodein = "waelrodein",
+    Wafimiromi = "wafimiromi",
+    Wafiradipa = "wafiradipa",
+    Wafiteun = "wafiteun",
+    Wanoteun = "wanoteun",
+    Waof = "waof",
+    Waonendice = "waonendice",
+    Wareismeal = "wareismeal",
+    Warerachat = "warerachat",
+    Wareunpaan = "wareunpaan",
+    Wataarab = "wataarab",
+    Wathense = "wathense",
+    Wawiabme = "wawiabme",
+    Wawiposa = "wawiposa",
+    Waxopo = "waxopo",
+    Wealtr = "wealtr",
+    Weasengrwe = "weasengrwe",
+    Wechaselab = "wechaselab",
+    Wedethchva = "wedethchva",
+    Wedi = "wedi",
+    Weexwe = "weexwe",
+    Weintr = "weintr",
+    Wemediof = "wemediof",
+    Wemiarde = "wemiarde",
+    Weraatmi = "weraatmi",
+    Wete = "wete",
+    Wetenedi = "wetenedi",
+    Weth = "weth",
+    Wethro = "wethro",
+    Wetipost = "wetipost",
+    Wetrchtawi = "wetrchtawi",
+    Weweabcesi = "weweabcesi",
+    Wiaccedest = "wiaccedest",
+    Wianpaelti = "wianpaelti",
+    Wiatxotiwa = "wiatxotiwa",
+    Wich = "wich",
+    Wicoinorno = "wicoinorno",
+    Wide = "wide",
+    Widialac = "widialac",
+    Wienalison = "wienalison",
+    Wiensean = "wiensean",
+    Wiexasas = "wiexasas",
+    Wiexpofi = "wiexpofi",
+    Wimaloab = "wimaloab",
+    Wimi = "wimi",
+    Wimiista = "wimiista",
+    Wior = "wior",
+    Wipo = "wipo",
+    Wireexmace = "wireexmace",
+    Wirorede = "wirorede",
+    Witr = "witr",
+    Wiva = "wiva",
+    Wiwaorte = "wiwaorte",
+    Wixoonce = "wixoonce",
+    Xoal = "xoal",
+    Xobe = "xobe",
+    Xoexat = "xoexat",
+    Xograronro = "xograronro",
+    Xogrcose = "xogrcose",
+    Xoinxoacof = "xoinxoacof",
+    Xoisxo = "xoisxo",
+    Xomevaat = "xomevaat",
+    Xomienst = "xomienst",
+    Xomima = "xomima",
+    Xonecechbe = "xonecechbe",
+    Xopo = "xopo",
+    Xoro = "xoro",
+    Xosaarneab = "xosaarneab",
+    Xosiabse = "xosiabse",
+    Xostwegrth = "xostwegrth",
+    Xotaofta = "xotaofta",
+    Xoungr = "xoungr",
+    Xowielbeti = "xowielbeti",
+}
+
+const ROUNDS: usize = 2_000;
+
+/// Times a strategy, which returns the index of the variant found
+fn bench(name: &str, inputs: &[String], lookup: impl Fn(&str) -> Option<usize>) -> Duration {
+    let start = Instant::now();
+    let mut found = 0usize;
+    for _ in 0..ROUNDS {
+        for input in inputs {
+            found += lookup(black_box(input)).map_or(0, |index| index + 1);
+        }
+    }
+    let elapsed = start.elapsed();
+    black_box(found);
+    let per_lookup = elapsed.as_nanos() as f64 / (ROUNDS * inputs.len()) as f64;
+    println!("{name:>6}: {elapsed:>10.2?} total, {per_lookup:>7.2} ns/lookup");
+    elapsed
+}
+
+fn main() {
+    // Every keyword, plus as many near misses
+    let mut inputs = KEYWORDS
+        .iter()
+        .map(|keyword| keyword.to_string())
+        .chain(KEYWORDS.iter().map(|keyword| format!("{keyword}s")))
+        .collect::<Vec<String>>();
+    // Shuffle deterministically so branch prediction can't learn the order
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    for i in (1..inputs.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        inputs.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+    let matched = |input: &str| Match::from_keyword(input).map(|variant| variant.index());
+    let trie = |input: &str| Trie::from_keyword(input).map(|variant| variant.index());
+    let phf = |input: &str| Phf::from_keyword(input).map(|variant| variant.index());
+    // Every strategy must find the same variant, or nothing, for every input
+    for (index, keyword) in KEYWORDS.iter().enumerate() {
+        assert_eq!(matched(keyword), Some(index));
+    }
+    for input in &inputs {
+        assert_eq!(trie(input), matched(input), "{input}");
+        assert_eq!(phf(input), matched(input), "{input}");
+    }
+    println!(
+        "{} keywords, {} lookups per strategy",
+        KEYWORDS.len(),
+        ROUNDS * inputs.len()
+    );
+    let matched = bench("match", &inputs, matched);
+    let trie = bench("trie", &inputs, trie);
+    let phf = bench("phf", &inputs, phf);
+    println!(
+        "speedup over match: trie {:.1}x, phf {:.1}x",
+        matched.as_secs_f64() / trie.as_secs_f64(),
+        matched.as_secs_f64() / phf.as_secs_f64()
+    );
+}
@@ -0,0 +1,145 @@
+//! Comparison traits keyed on a forward function, enabled with
+//! `#[assoc_ord(by = func)]` and `#[assoc_eq(by = func)]` on the enum. The
+//! latter may also name another type, as in `#[assoc_eq(u16, by = code)]`, to
+//! compare variants with values of that type.
+
+use quote::quote;
+use syn::{Result, Token, Variant};
+
+use crate::{ASSOC_EQ_ATTR, ASSOC_ORD_ATTR};
+
+/// The arguments of an `assoc_ord` or `assoc_eq` attribute, of form
+/// `by = func`, optionally preceded by a type for `assoc_eq`
+struct CmpDecl {
+    ty: Option<syn::Type>,
+    by: syn::Ident,
+}
+
+impl syn::parse::Parse for CmpDecl {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let by_follows = input.peek2(Token![=])
+            && input
+                .fork()
+                .parse::<syn::Ident>()
+                .is_ok_and(|ident| ident == "by");
+        let ty = if by_follows {
+            None
+        } else {
+            let ty = input.parse()?;
+            input.parse::<Token![,]>()?;
+            Some(ty)
+        };
+        let by_token: syn::Ident = input.parse()?;
+        if by_token != "by" {
+            return Err(syn::Error::new_spanned(by_token, "Expected `by = func`"));
+        }
+        input.parse::<Token![=]>()?;
+        let by = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self { ty, by })
+    }
+}
+
+/// Builds the comparison trait implementations of an enum, which are placed
+/// alongside the enum
+pub(crate) fn build_cmp(
+    ast: &syn::DeriveInput,
+    variants: &[&Variant],
+) -> Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let mut impls = Vec::new();
+    for attr in &ast.attrs {
+        if attr.path().is_ident(ASSOC_ORD_ATTR) {
+            let decl: CmpDecl = attr.parse_args()?;
+            if let Some(ty) = &decl.ty {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "`assoc_ord` only takes `by = func`",
+                ));
+            }
+            let by = &decl.by;
+            let tie_break = if eq_by(ast)?.is_some_and(|eq_by| eq_by == *by) {
+                quote!()
+            } else {
+                // Equal values are ordered by declaration, to agree with a
+                // derived `Eq`
+                let indices = variants.iter().enumerate().map(|(index, variant)| {
+                    let var_ident = &variant.ident;
+                    quote!(Self::#var_ident { .. } => #index,)
+                });
+                quote! {
+                    .then_with(|| {
+                        let index = |value: &Self| match value {
+                            #(#indices)*
+                        };
+                        ::core::cmp::Ord::cmp(&index(self), &index(other))
+                    })
+                }
+            };
+            impls.push(quote! {
+                impl #impl_generics ::core::cmp::PartialOrd for #name #ty_generics #where_clause {
+                    fn partial_cmp(
+                        &self,
+                        other: &Self,
+                    ) -> ::core::option::Option<::core::cmp::Ordering> {
+                        ::core::option::Option::Some(::core::cmp::Ord::cmp(self, other))
+                    }
+                }
+
+                impl #impl_generics ::core::cmp::Ord for #name #ty_generics #where_clause {
+                    fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                        ::core::cmp::Ord::cmp(&self.#by(), &other.#by())#tie_break
+                    }
+                }
+            });
+        } else if attr.path().is_ident(ASSOC_EQ_ATTR) {
+            let decl: CmpDecl = attr.parse_args()?;
+            let by = &decl.by;
+            impls.push(match &decl.ty {
+                None => quote! {
+                    impl #impl_generics ::core::cmp::PartialEq for #name #ty_generics #where_clause {
+                        fn eq(&self, other: &Self) -> bool {
+                            ::core::cmp::PartialEq::eq(&self.#by(), &other.#by())
+                        }
+                    }
+
+                    impl #impl_generics ::core::cmp::Eq for #name #ty_generics #where_clause {}
+
+                    impl #impl_generics ::core::hash::Hash for #name #ty_generics #where_clause {
+                        fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                            ::core::hash::Hash::hash(&self.#by(), state)
+                        }
+                    }
+                },
+                Some(ty) => quote! {
+                    impl #impl_generics ::core::cmp::PartialEq<#ty> for #name #ty_generics #where_clause {
+                        fn eq(&self, other: &#ty) -> bool {
+                            ::core::cmp::PartialEq::eq(&self.#by(), other)
+                        }
+                    }
+
+                    impl #impl_generics ::core::cmp::PartialEq<#name #ty_generics> for #ty #where_clause {
+                        fn eq(&self, other: &#name #ty_generics) -> bool {
+                            ::core::cmp::PartialEq::eq(self, &other.#by())
+                        }
+                    }
+                },
+            });
+        }
+    }
+    Ok(quote!(#(#impls)*))
+}
+
+/// The function `PartialEq` is implemented by with `assoc_eq`, if any
+fn eq_by(ast: &syn::DeriveInput) -> Result<Option<syn::Ident>> {
+    for attr in &ast.attrs {
+        if attr.path().is_ident(ASSOC_EQ_ATTR) {
+            let decl: CmpDecl = attr.parse_args()?;
+            if decl.ty.is_none() {
+                return Ok(Some(decl.by));
+            }
+        }
+    }
+    Ok(None)
+}
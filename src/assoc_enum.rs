@@ -0,0 +1,241 @@
+//! The `assoc_enum!` macro, which defines an enum and its associations as a
+//! table. The header holds the functions, exactly as they would be written in
+//! a `func` attribute, and each row holds a variant followed by one value per
+//! function:
+//!
+//! ```text
+//! assoc_enum! {
+//!     #[derive(Debug)]
+//!     pub enum Op(pub fn code(&self) -> u8, pub fn from_name(s: &str) -> Option<Self>) {
+//!         Read => 0x10, "read" | "r";
+//!         Write => 0x20, "write";
+//!     }
+//! }
+//! ```
+//!
+//! The table is converted into `func` and `assoc` attributes and expanded in
+//! the same way as `#[derive(Assoc)]`. Each function gets its own `func`
+//! attribute, so that associations are not shared between them.
+
+use quote::quote;
+use syn::{Error, Result, Token, Variant, parse::Parser, punctuated::Punctuated};
+
+use crate::{ASSOC_ATTR, DeriveFunc, DeriveFuncs, FUNC_ATTR, FuncItem, HELPER_ATTRS, impl_macro};
+
+pub(crate) struct AssocEnum {
+    attrs: Vec<syn::Attribute>,
+    vis: syn::Visibility,
+    enum_token: Token![enum],
+    ident: syn::Ident,
+    generics: syn::Generics,
+    /// The functions of the header, each with the tokens of its `func`
+    /// attribute
+    funcs: Vec<(DeriveFunc, proc_macro2::TokenStream)>,
+    brace_token: syn::token::Brace,
+    rows: Vec<Row>,
+}
+
+/// A variant and its value for each function in the header, where an empty
+/// cell means the variant has no association for that function
+struct Row {
+    variant: Variant,
+    cells: Vec<Option<proc_macro2::TokenStream>>,
+}
+
+impl syn::parse::Parse for AssocEnum {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let enum_token = input.parse()?;
+        let ident = input.parse()?;
+        let mut generics: syn::Generics = input.parse()?;
+        let content;
+        syn::parenthesized!(content in input);
+        let funcs = parse_header(&content)?;
+        generics.where_clause = input.parse()?;
+        let content;
+        let brace_token = syn::braced!(content in input);
+        let mut rows = Vec::new();
+        while !content.is_empty() {
+            rows.push(Row::parse(&content, funcs.len(), |index| {
+                funcs[index].0.has_self()
+            })?);
+        }
+        Ok(Self {
+            attrs,
+            vis,
+            enum_token,
+            ident,
+            generics,
+            funcs,
+            brace_token,
+            rows,
+        })
+    }
+}
+
+impl Row {
+    /// Parses a row of form `Variant => value, value, ...;`, where each value is
+    /// an expression for forward functions and a pattern for reverse functions
+    fn parse(
+        input: syn::parse::ParseStream,
+        columns: usize,
+        is_forward: impl Fn(usize) -> bool,
+    ) -> Result<Self> {
+        let variant = parse_variant(input)?;
+        input.parse::<Token![=>]>()?;
+        let mut cells = Vec::new();
+        loop {
+            let cell = if input.is_empty() || input.peek(Token![,]) || input.peek(Token![;]) {
+                None
+            } else if cells.len() < columns && is_forward(cells.len()) {
+                let expr: syn::Expr = input.parse()?;
+                Some(quote!(#expr))
+            } else {
+                let pat = syn::Pat::parse_multi_with_leading_vert(input)?;
+                Some(quote!(#pat))
+            };
+            cells.push(cell);
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            } else {
+                break;
+            }
+        }
+        // Allow a trailing comma
+        if cells.len() == columns + 1 && cells.last().is_some_and(Option::is_none) {
+            cells.pop();
+        }
+        if cells.len() != columns {
+            return Err(Error::new_spanned(
+                &variant.ident,
+                format!(
+                    "Expected {} values for {}, found {}",
+                    columns,
+                    variant.ident,
+                    cells.len()
+                ),
+            ));
+        }
+        if !input.is_empty() {
+            input.parse::<Token![;]>()?;
+        }
+        Ok(Self { variant, cells })
+    }
+}
+
+impl AssocEnum {
+    pub(crate) fn expand(self) -> Result<proc_macro2::TokenStream> {
+        let func_attr_ident = syn::Ident::new(FUNC_ATTR, proc_macro2::Span::call_site());
+        let assoc_attr_ident = syn::Ident::new(ASSOC_ATTR, proc_macro2::Span::call_site());
+        let mut attrs = self.attrs;
+        for (_, tokens) in &self.funcs {
+            attrs.push(syn::parse_quote!(#[#func_attr_ident(#tokens)]));
+        }
+        let variants = self
+            .rows
+            .into_iter()
+            .map(|Row { mut variant, cells }| {
+                for ((func, _), cell) in self.funcs.iter().zip(cells) {
+                    if let Some(cell) = cell {
+                        let func_ident = &func.sig.ident;
+                        variant
+                            .attrs
+                            .push(syn::parse_quote!(#[#assoc_attr_ident(#func_ident = #cell)]));
+                    }
+                }
+                variant
+            })
+            .collect::<Punctuated<Variant, Token![,]>>();
+        let mut ast = syn::DeriveInput {
+            attrs,
+            vis: self.vis,
+            ident: self.ident,
+            generics: self.generics,
+            data: syn::Data::Enum(syn::DataEnum {
+                enum_token: self.enum_token,
+                brace_token: self.brace_token,
+                variants,
+            }),
+        };
+        let assoc_impl = impl_macro(&ast)?;
+        // The helper attributes are only understood by the derive macro
+        strip_helper_attrs(&mut ast.attrs);
+        if let syn::Data::Enum(data) = &mut ast.data {
+            for variant in &mut data.variants {
+                strip_helper_attrs(&mut variant.attrs);
+                for field in &mut variant.fields {
+                    strip_helper_attrs(&mut field.attrs);
+                }
+            }
+        }
+        Ok(quote! {
+            #ast
+
+            #assoc_impl
+        })
+    }
+}
+
+/// Parses the functions of the header, splitting its tokens into one group per
+/// function along with the options which follow it
+fn parse_header(
+    input: syn::parse::ParseStream,
+) -> Result<Vec<(DeriveFunc, proc_macro2::TokenStream)>> {
+    let mut groups: Vec<proc_macro2::TokenStream> = Vec::new();
+    while !input.is_empty() {
+        let start = input.cursor();
+        let item: FuncItem = input.parse()?;
+        let mut tokens = proc_macro2::TokenStream::new();
+        let mut cursor = start;
+        while cursor != input.cursor() {
+            let (token, next) = cursor.token_tree().expect("Parsed tokens remain");
+            tokens.extend([token]);
+            cursor = next;
+        }
+        match (item, groups.last_mut()) {
+            (FuncItem::Option(_), Some(group)) => group.extend(quote!(, #tokens)),
+            // An option without a function is reported when the group is parsed
+            _ => groups.push(tokens),
+        }
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+    }
+    groups
+        .into_iter()
+        .map(|tokens| {
+            let DeriveFuncs(mut funcs) = DeriveFuncs::parse_list.parse2(tokens.clone())?;
+            Ok((funcs.remove(0), tokens))
+        })
+        .collect()
+}
+
+/// Parses a variant without mistaking the `=>` which follows it for the start
+/// of a discriminant
+fn parse_variant(input: syn::parse::ParseStream) -> Result<Variant> {
+    let attrs = input.call(syn::Attribute::parse_outer)?;
+    let ident = input.parse()?;
+    let fields = if input.peek(syn::token::Brace) {
+        syn::Fields::Named(input.parse()?)
+    } else if input.peek(syn::token::Paren) {
+        syn::Fields::Unnamed(input.parse()?)
+    } else {
+        syn::Fields::Unit
+    };
+    let discriminant = if input.peek(Token![=]) && !input.peek(Token![=>]) {
+        Some((input.parse()?, input.parse()?))
+    } else {
+        None
+    };
+    Ok(Variant {
+        attrs,
+        ident,
+        fields,
+        discriminant,
+    })
+}
+
+fn strip_helper_attrs(attrs: &mut Vec<syn::Attribute>) {
+    attrs.retain(|attr| !HELPER_ATTRS.iter().any(|name| attr.path().is_ident(name)));
+}
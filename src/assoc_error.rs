@@ -0,0 +1,146 @@
+//! `Display` and `Error` implementations for error enums, enabled with
+//! `#[assoc_error(message = func, source = ...)]` on the enum. The message is
+//! the value of a forward function, while the source is either a forward
+//! function returning an `Option` of a reference to an error, or a field which
+//! holds the source in the variants having it.
+
+use quote::quote;
+use syn::{Error, Result, Token, Variant};
+
+use crate::{ASSOC_ERROR_ATTR, DeriveFuncs};
+
+/// The arguments of an `assoc_error` attribute
+struct ErrorDecl {
+    message: syn::Ident,
+    source: Option<syn::Member>,
+}
+
+impl syn::parse::Parse for ErrorDecl {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let mut message = None;
+        let mut source = None;
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "message" => message = Some(input.parse()?),
+                "source" => source = Some(input.parse()?),
+                _ => {
+                    return Err(Error::new_spanned(
+                        &key,
+                        format!("Unknown `assoc_error` option `{}`", key),
+                    ));
+                }
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        let message =
+            message.ok_or_else(|| input.error("`assoc_error` requires a `message` function"))?;
+        Ok(Self { message, source })
+    }
+}
+
+/// Builds the `Display` and `Error` implementations of an enum, which are
+/// placed alongside the enum
+pub(crate) fn build_error(
+    ast: &syn::DeriveInput,
+    variants: &[&Variant],
+    fns: &[DeriveFuncs],
+) -> Result<proc_macro2::TokenStream> {
+    let Some(attr) = ast
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(ASSOC_ERROR_ATTR))
+    else {
+        return Ok(quote!());
+    };
+    let decl: ErrorDecl = attr.parse_args()?;
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let message = &decl.message;
+    let source = match &decl.source {
+        None => quote!(::core::option::Option::None),
+        // A function takes precedence over a field of the same name
+        Some(syn::Member::Named(func))
+            if fns
+                .iter()
+                .flat_map(|DeriveFuncs(funcs)| funcs)
+                .any(|derive_func| derive_func.sig.ident == *func) =>
+        {
+            quote! {
+                ::core::option::Option::map(self.#func(), |source| {
+                    source as &(dyn ::core::error::Error + 'static)
+                })
+            }
+        }
+        Some(member) => {
+            let arms = variants
+                .iter()
+                .filter_map(|variant| {
+                    let field = variant
+                        .fields
+                        .iter()
+                        .zip(variant.fields.members())
+                        .find(|(_, field_member)| field_member == member)?
+                        .0;
+                    let var_ident = &variant.ident;
+                    // A boxed `dyn Error` does not implement `Error` itself, so
+                    // the source is the error within
+                    let source = if is_box(&field.ty) {
+                        quote!(&**source)
+                    } else {
+                        quote!(source)
+                    };
+                    Some(quote! {
+                        Self::#var_ident { #member: source, .. } => {
+                            ::core::option::Option::Some(
+                                #source as &(dyn ::core::error::Error + 'static),
+                            )
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            if arms.is_empty() {
+                return Err(Error::new_spanned(
+                    member,
+                    "The source must be a function or a field of at least one variant",
+                ));
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    };
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(&self.#message(), f)
+            }
+        }
+
+        impl #impl_generics ::core::error::Error for #name #ty_generics #where_clause {
+            #[allow(unreachable_patterns)]
+            fn source(&self) -> ::core::option::Option<&(dyn ::core::error::Error + 'static)> {
+                #source
+            }
+        }
+    })
+}
+
+/// Whether the type of a source field is a `Box`, such as
+/// `Box<dyn Error + Send + Sync>`
+fn is_box(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Box"),
+        _ => false,
+    }
+}
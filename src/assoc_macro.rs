@@ -0,0 +1,110 @@
+//! Declarative macros over the variants, enabled with
+//! `#[assoc_macro(func1, func2)]` on the enum. This generates a `macro_rules!`
+//! named `{enum_name}_variants`, which invokes a callback macro once per variant
+//! with the variant's ident and its values for the listed forward functions, so
+//! that `job_variants!(register)` expands to `register!(Queued, 1, "queued");`
+//! and so on. Listing `export` as well exports the macro for other crates.
+
+use quote::{format_ident, quote};
+use syn::{Error, Result, Token, Variant, punctuated::Punctuated};
+
+use crate::{
+    ASSOC_MACRO_ATTR, Association, AssociationType, DeriveFunc, DeriveFuncs, build_fwd_default,
+    build_fwd_expr, option_inner, placeholder, to_snake_case,
+};
+
+/// The option exporting the macro with `#[macro_export]`
+const EXPORT_OPTION: &str = "export";
+
+/// Builds the macro of an enum, which is placed alongside the enum
+pub(crate) fn build_macro(
+    ast: &syn::DeriveInput,
+    variants: &[&Variant],
+    fns: &[DeriveFuncs],
+) -> Result<proc_macro2::TokenStream> {
+    let Some(attr) = ast
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(ASSOC_MACRO_ATTR))
+    else {
+        return Ok(quote!());
+    };
+    let func_idents = match &attr.meta {
+        syn::Meta::Path(_) => Punctuated::new(),
+        _ => attr.parse_args_with(Punctuated::<syn::Ident, Token![,]>::parse_terminated)?,
+    };
+    let export = func_idents.iter().any(|ident| ident == EXPORT_OPTION);
+    let funcs = func_idents
+        .iter()
+        .filter(|ident| *ident != EXPORT_OPTION)
+        .map(|ident| {
+            fns.iter()
+                .flat_map(|DeriveFuncs(funcs)| funcs)
+                .find(|func| func.sig.ident == *ident)
+                .filter(|func| func.has_self())
+                .ok_or_else(|| {
+                    Error::new_spanned(ident, format!("Unknown forward function `{}`", ident))
+                })
+        })
+        .collect::<Result<Vec<&DeriveFunc>>>()?;
+    let infos = placeholder::variant_infos(variants);
+    let calls = variants
+        .iter()
+        .zip(&infos)
+        .map(|(variant, info)| {
+            let var_ident = &variant.ident;
+            let values = funcs
+                .iter()
+                .map(|func| build_value(variant, info, func))
+                .collect::<Result<Vec<proc_macro2::TokenStream>>>()?;
+            Ok(quote!($($callback)::+!(#var_ident #(, #values)*);))
+        })
+        .collect::<Result<Vec<proc_macro2::TokenStream>>>()?;
+    let name = &ast.ident;
+    let macro_ident = format_ident!("{}_variants", to_snake_case(&name.to_string()));
+    let export = export.then(|| quote!(#[macro_export]));
+    Ok(quote! {
+        #[allow(unused_macros)]
+        #export
+        macro_rules! #macro_ident {
+            ($($callback:ident)::+) => {
+                #(#calls)*
+            };
+        }
+    })
+}
+
+/// The value of a forward function for a variant, as written in its
+/// association or default. Values of functions returning an `Option` are
+/// wrapped in `Some` like the function's own.
+fn build_value(
+    variant: &Variant,
+    info: &placeholder::VariantInfo,
+    func: &DeriveFunc,
+) -> Result<proc_macro2::TokenStream> {
+    let ident = &func.sig.ident;
+    let is_option = match &func.sig.output {
+        syn::ReturnType::Type(_, ty) => option_inner(ty).is_some(),
+        syn::ReturnType::Default => false,
+    };
+    let values = Association::get_variant_assocs(variant, false)
+        .filter(|assoc| assoc.func == *ident)
+        .map(|assoc| match assoc.assoc {
+            AssociationType::Forward(expr) => Ok(expr),
+            _ => Err(Error::new_spanned(
+                variant,
+                format!("`assoc_macro` only supports plain values for {}", ident),
+            )),
+        })
+        .collect::<Result<Vec<syn::Expr>>>()?;
+    match values.as_slice() {
+        [] => build_fwd_default(info, is_option, func)?.ok_or_else(|| {
+            Error::new_spanned(variant, format!("Missing `assoc` attribute for {}", ident))
+        }),
+        [value] => build_fwd_expr(value, info, is_option, func),
+        _ => Err(Error::new_spanned(
+            variant,
+            format!("Too many `assoc` attributes for {}", ident),
+        )),
+    }
+}
@@ -0,0 +1,235 @@
+//! Mappings between enums, declared with
+//! `#[assoc_map(Target, to = to_target, from = from_target)]` on the enum and
+//! `#[assoc(to_target = Target::Variant)]` on each variant. Both directions are
+//! generated from the same associations, which must map distinct variants to
+//! distinct targets.
+
+use quote::{quote, quote_spanned};
+use syn::{Error, Result, Token, Variant, spanned::Spanned};
+
+use crate::{ASSOC_MAP_ATTR, Association, AssociationType};
+
+/// A mapping declared within an `assoc_map` attribute
+struct MapDecl {
+    target: syn::Type,
+    to: Option<syn::Ident>,
+    from: Option<syn::Ident>,
+    /// Whether every variant of both enums must be mapped
+    total: bool,
+}
+
+impl syn::parse::Parse for MapDecl {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let target = input.parse()?;
+        let mut decl = Self {
+            target,
+            to: None,
+            from: None,
+            total: false,
+        };
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let option: syn::Ident = input.parse()?;
+            match option.to_string().as_str() {
+                "to" | "from" => {
+                    input.parse::<Token![=]>()?;
+                    let name = Some(input.parse()?);
+                    if option == "to" {
+                        decl.to = name;
+                    } else {
+                        decl.from = name;
+                    }
+                }
+                "total" => decl.total = true,
+                _ => {
+                    return Err(Error::new_spanned(
+                        &option,
+                        format!("Unknown `assoc_map` option `{}`", option),
+                    ));
+                }
+            }
+        }
+        Ok(decl)
+    }
+}
+
+impl MapDecl {
+    /// The name associations are given under, which is the `to` function or
+    /// the `from` function when only that is generated
+    fn key(&self) -> Option<&syn::Ident> {
+        self.to.as_ref().or(self.from.as_ref())
+    }
+}
+
+/// Builds the functions of every mapping declared for an enum. The result is
+/// placed within the same `impl` block as the associated functions.
+pub(crate) fn build_maps(
+    ast: &syn::DeriveInput,
+    variants: &[&Variant],
+) -> Result<proc_macro2::TokenStream> {
+    let decls = ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(ASSOC_MAP_ATTR))
+        .map(|attr| {
+            let decl: MapDecl = attr.parse_args()?;
+            if decl.key().is_none() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "`assoc_map` requires a `to` or `from` function",
+                ));
+            }
+            Ok(decl)
+        })
+        .collect::<Result<Vec<MapDecl>>>()?;
+    decls
+        .iter()
+        .map(|decl| build_map(&ast.vis, variants, decl))
+        .collect()
+}
+
+fn build_map(
+    vis: &syn::Visibility,
+    variants: &[&Variant],
+    decl: &MapDecl,
+) -> Result<proc_macro2::TokenStream> {
+    let key = decl.key().expect("Checked when parsing");
+    let target = &decl.target;
+    let mut mappings: Vec<(&Variant, syn::Path)> = Vec::new();
+    for variant in variants {
+        let mut paths = Association::get_variant_assocs(variant, false)
+            .filter(|assoc| assoc.func == *key)
+            .filter_map(|assoc| match assoc.assoc {
+                AssociationType::Forward(syn::Expr::Path(expr)) => Some(Ok(expr.path)),
+                AssociationType::Forward(expr) => Some(Err(Error::new_spanned(
+                    expr,
+                    "Expected a variant of the target enum",
+                ))),
+                _ => None,
+            })
+            .collect::<Result<Vec<syn::Path>>>()?;
+        match paths.len() {
+            0 if decl.total => {
+                return Err(Error::new_spanned(
+                    variant,
+                    format!("Missing `assoc` attribute for {}", key),
+                ));
+            }
+            0 => (),
+            1 => {
+                let path = paths.remove(0);
+                let tokens = quote!(#path).to_string();
+                if let Some((other, _)) = mappings
+                    .iter()
+                    .find(|(_, other)| quote!(#other).to_string() == tokens)
+                {
+                    return Err(Error::new_spanned(
+                        &path,
+                        format!(
+                            "`{}` is already mapped from `{}`",
+                            tokens.replace(' ', ""),
+                            other.ident
+                        ),
+                    ));
+                }
+                if decl.from.is_some() && !matches!(variant.fields, syn::Fields::Unit) {
+                    return Err(Error::new_spanned(
+                        variant,
+                        "Mappings of variants with fields cannot be reversed",
+                    ));
+                }
+                mappings.push((variant, path));
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    variant,
+                    format!("Too many `assoc` attributes for {}", key),
+                ));
+            }
+        }
+    }
+    let target_name = quote!(#target).to_string().replace(' ', "");
+    let (to_doc, from_doc) = if decl.total {
+        (
+            format!("Returns the `{}` this variant maps to", target_name),
+            format!("Returns the variant the given `{}` maps from", target_name),
+        )
+    } else {
+        (
+            format!("Returns the `{}` this variant maps to, if any", target_name),
+            format!(
+                "Returns the variant the given `{}` maps from, if any",
+                target_name
+            ),
+        )
+    };
+    let to = decl.to.as_ref().map(|to| {
+        let arms = mappings.iter().map(|(variant, path)| {
+            let var_ident = &variant.ident;
+            if decl.total {
+                quote!(Self::#var_ident { .. } => #path,)
+            } else {
+                quote!(Self::#var_ident { .. } => ::core::option::Option::Some(#path),)
+            }
+        });
+        if decl.total {
+            quote! {
+                #[doc = #to_doc]
+                #vis const fn #to(&self) -> #target {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        } else {
+            quote! {
+                #[doc = #to_doc]
+                #[allow(unreachable_patterns)]
+                #vis const fn #to(&self) -> ::core::option::Option<#target> {
+                    match self {
+                        #(#arms)*
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+        }
+    });
+    let from = decl.from.as_ref().map(|from| {
+        let arms = mappings.iter().map(|(variant, path)| {
+            let var_ident = &variant.ident;
+            if decl.total {
+                quote!(#path => Self::#var_ident,)
+            } else {
+                quote!(#path => ::core::option::Option::Some(Self::#var_ident),)
+            }
+        });
+        // Without a wildcard, a variant added to the target enum fails to
+        // compile until it is mapped, which is reported at the target
+        let span = target.span();
+        if decl.total {
+            quote_spanned! {span=>
+                #[doc = #from_doc]
+                #vis fn #from(value: #target) -> Self {
+                    match value {
+                        #(#arms)*
+                    }
+                }
+            }
+        } else {
+            quote_spanned! {span=>
+                #[doc = #from_doc]
+                #[allow(unreachable_patterns)]
+                #vis fn #from(value: #target) -> ::core::option::Option<Self> {
+                    match value {
+                        #(#arms)*
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+        }
+    });
+    Ok(quote!(#to #from))
+}
@@ -0,0 +1,298 @@
+//! Bitsets of variants, enabled with `#[assoc_set]` on the enum. This generates
+//! a companion type named `{EnumName}Set`, or the name given as in
+//! `#[assoc_set(Permissions)]`, backed by the smallest unsigned integer with a
+//! bit for every variant.
+
+use quote::{format_ident, quote};
+use syn::{Error, Result, Variant, ext::IdentExt};
+
+use crate::ASSOC_SET_ATTR;
+
+/// Builds the set type of an enum, which is placed alongside the enum
+pub(crate) fn build_set(
+    ast: &syn::DeriveInput,
+    variants: &[&Variant],
+) -> Result<proc_macro2::TokenStream> {
+    let Some(attr) = ast
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(ASSOC_SET_ATTR))
+    else {
+        return Ok(quote!());
+    };
+    let name = &ast.ident;
+    let set_ident = match &attr.meta {
+        syn::Meta::Path(_) => format_ident!("{}Set", name),
+        _ => attr.parse_args()?,
+    };
+    if !ast.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            attr,
+            "`assoc_set` is not supported for generic enums",
+        ));
+    }
+    if let Some(variant) = variants
+        .iter()
+        .find(|variant| !matches!(variant.fields, syn::Fields::Unit))
+    {
+        return Err(Error::new_spanned(
+            variant,
+            "`assoc_set` requires every variant to be a unit variant",
+        ));
+    }
+    let count = variants.len();
+    let (bits, width) = match count {
+        0 => {
+            return Err(Error::new_spanned(
+                attr,
+                "`assoc_set` requires at least one variant",
+            ));
+        }
+        1..=8 => (quote!(u8), 8),
+        9..=16 => (quote!(u16), 16),
+        17..=32 => (quote!(u32), 32),
+        33..=64 => (quote!(u64), 64),
+        65..=128 => (quote!(u128), 128),
+        _ => {
+            return Err(Error::new_spanned(
+                attr,
+                "`assoc_set` supports at most 128 variants",
+            ));
+        }
+    };
+    let mask = if count == width {
+        u128::MAX >> (128 - width)
+    } else {
+        (1u128 << count) - 1
+    };
+    let mask = proc_macro2::Literal::u128_unsuffixed(mask);
+    let vis = &ast.vis;
+    let var_idents = variants
+        .iter()
+        .map(|variant| &variant.ident)
+        .collect::<Vec<_>>();
+    let var_names = var_idents.iter().map(|ident| ident.unraw().to_string());
+    let indices = (0..count).collect::<Vec<usize>>();
+    let doc = format!("A set of [`{}`] variants, stored as a bitmask", name);
+    let ops = [
+        ("BitOr", "bitor", "union"),
+        ("BitAnd", "bitand", "intersection"),
+        ("BitXor", "bitxor", "symmetric_difference"),
+        ("Sub", "sub", "difference"),
+    ]
+    .map(|(op, op_fn, method)| {
+        let (op, op_fn, method) = (
+            format_ident!("{}", op),
+            format_ident!("{}", op_fn),
+            format_ident!("{}", method),
+        );
+        quote! {
+            impl ::core::ops::#op for #set_ident {
+                type Output = Self;
+
+                fn #op_fn(self, other: Self) -> Self {
+                    self.#method(other)
+                }
+            }
+        }
+    });
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::default::Default,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::hash::Hash,
+        )]
+        #vis struct #set_ident {
+            bits: #bits,
+        }
+
+        #[allow(dead_code)]
+        impl #set_ident {
+            /// The number of variants, which is the capacity of the set
+            #vis const CAPACITY: usize = #count;
+
+            /// Returns an empty set
+            #vis const fn new() -> Self {
+                Self { bits: 0 }
+            }
+
+            /// Returns a set of every variant
+            #vis const fn all() -> Self {
+                Self { bits: #mask }
+            }
+
+            /// Returns the set with the given bits, where bit `n` is the
+            /// variant at index `n`. Bits without a variant are ignored.
+            #vis const fn from_bits(bits: #bits) -> Self {
+                Self { bits: bits & #mask }
+            }
+
+            /// Returns the bits of the set, where bit `n` is the variant at
+            /// index `n`
+            #vis const fn bits(&self) -> #bits {
+                self.bits
+            }
+
+            const fn bit(value: &#name) -> #bits {
+                1 << match value {
+                    #(#name::#var_idents => #indices,)*
+                }
+            }
+
+            /// Returns this set with the variant added
+            #vis const fn with(self, value: #name) -> Self {
+                Self { bits: self.bits | Self::bit(&value) }
+            }
+
+            /// Returns this set with the variant removed
+            #vis const fn without(self, value: #name) -> Self {
+                Self { bits: self.bits & !Self::bit(&value) }
+            }
+
+            /// Whether the set contains the variant
+            #vis const fn contains(&self, value: &#name) -> bool {
+                self.bits & Self::bit(value) != 0
+            }
+
+            /// Adds the variant, returning whether it was not already present
+            #vis fn insert(&mut self, value: #name) -> bool {
+                let added = !self.contains(&value);
+                self.bits |= Self::bit(&value);
+                added
+            }
+
+            /// Removes the variant, returning whether it was present
+            #vis fn remove(&mut self, value: &#name) -> bool {
+                let removed = self.contains(value);
+                self.bits &= !Self::bit(value);
+                removed
+            }
+
+            /// Removes every variant
+            #vis fn clear(&mut self) {
+                self.bits = 0;
+            }
+
+            /// Returns the number of variants in the set
+            #vis const fn len(&self) -> usize {
+                self.bits.count_ones() as usize
+            }
+
+            /// Whether the set is empty
+            #vis const fn is_empty(&self) -> bool {
+                self.bits == 0
+            }
+
+            /// Returns the variants in either set
+            #vis const fn union(self, other: Self) -> Self {
+                Self { bits: self.bits | other.bits }
+            }
+
+            /// Returns the variants in both sets
+            #vis const fn intersection(self, other: Self) -> Self {
+                Self { bits: self.bits & other.bits }
+            }
+
+            /// Returns the variants in this set but not the other
+            #vis const fn difference(self, other: Self) -> Self {
+                Self { bits: self.bits & !other.bits }
+            }
+
+            /// Returns the variants in exactly one of the sets
+            #vis const fn symmetric_difference(self, other: Self) -> Self {
+                Self { bits: self.bits ^ other.bits }
+            }
+
+            /// Returns the variants not in this set
+            #vis const fn complement(self) -> Self {
+                Self { bits: !self.bits & #mask }
+            }
+
+            /// Whether every variant of this set is in the other
+            #vis const fn is_subset(&self, other: &Self) -> bool {
+                self.bits & !other.bits == 0
+            }
+
+            /// Whether every variant of the other set is in this one
+            #vis const fn is_superset(&self, other: &Self) -> bool {
+                other.is_subset(self)
+            }
+
+            /// Whether the sets have no variant in common
+            #vis const fn is_disjoint(&self, other: &Self) -> bool {
+                self.bits & other.bits == 0
+            }
+
+            /// Returns the set of variants for which the predicate holds, eg.
+            /// `from_fn(Enum::is_admin)`
+            #vis fn from_fn(mut predicate: impl FnMut(&#name) -> bool) -> Self {
+                let mut set = Self::new();
+                for value in Self::all().iter() {
+                    if predicate(&value) {
+                        set.insert(value);
+                    }
+                }
+                set
+            }
+
+            /// Returns an iterator over the variants in the set, in
+            /// declaration order
+            #vis fn iter(&self) -> impl ::core::iter::Iterator<Item = #name> + use<> {
+                let bits = self.bits;
+                (0..#count)
+                    .filter(move |index| bits >> index & 1 != 0)
+                    .map(|index| match index {
+                        #(#indices => #name::#var_idents,)*
+                        _ => ::core::unreachable!(),
+                    })
+            }
+        }
+
+        impl ::core::convert::From<#name> for #set_ident {
+            fn from(value: #name) -> Self {
+                Self::new().with(value)
+            }
+        }
+
+        impl ::core::iter::FromIterator<#name> for #set_ident {
+            fn from_iter<I: ::core::iter::IntoIterator<Item = #name>>(iter: I) -> Self {
+                let mut set = Self::new();
+                set.extend(iter);
+                set
+            }
+        }
+
+        impl ::core::iter::Extend<#name> for #set_ident {
+            fn extend<I: ::core::iter::IntoIterator<Item = #name>>(&mut self, iter: I) {
+                for value in iter {
+                    self.insert(value);
+                }
+            }
+        }
+
+        #(#ops)*
+
+        impl ::core::ops::Not for #set_ident {
+            type Output = Self;
+
+            fn not(self) -> Self {
+                self.complement()
+            }
+        }
+
+        impl ::core::fmt::Debug for #set_ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                const NAMES: [&str; #count] = [#(#var_names),*];
+                let mut set = f.debug_set();
+                for index in (0..#count).filter(|index| self.bits >> index & 1 != 0) {
+                    set.entry(&::core::format_args!("{}", NAMES[index]));
+                }
+                set.finish()
+            }
+        }
+    })
+}
@@ -0,0 +1,588 @@
+//! The `assoc_source` attribute, which reads variants and their associations
+//! from a data file during expansion. Files are read relative to the
+//! `CARGO_MANIFEST_DIR` of the crate being compiled, and the format is chosen
+//! by the file extension. Only flat tables are supported:
+//!
+//! - CSV: a header row naming the columns, then one record per variant
+//! - JSON: an array of objects whose values are strings, numbers, booleans or
+//!   `null`
+//! - TOML: a single array of tables (`[[name]]`), with one table per variant
+//!   whose values are strings, numbers or booleans
+//!
+//! Missing values (empty CSV fields, `null` or absent keys) result in no
+//! association for that variant.
+
+use std::path::PathBuf;
+
+use quote::{ToTokens, quote};
+use syn::{Error, Result, Token, punctuated::Punctuated};
+
+use crate::{ASSOC_ATTR, DeriveFunc, DeriveFuncs, FUNC_ATTR};
+
+/// The arguments of an `assoc_source` attribute
+pub(crate) struct SourceArgs {
+    path: syn::LitStr,
+    variant: syn::LitStr,
+    /// Each function and the column holding its values
+    funcs: Vec<(syn::Ident, syn::LitStr)>,
+}
+
+impl syn::parse::Parse for SourceArgs {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let mut path = None;
+        let mut variant = None;
+        let mut funcs = Vec::new();
+        let args = Punctuated::<SourceArg, Token![,]>::parse_terminated(input)?;
+        for arg in args {
+            match arg {
+                SourceArg::Path(lit) => path = Some(lit),
+                SourceArg::Variant(lit) => variant = Some(lit),
+                SourceArg::Funcs(list) => funcs.extend(list),
+            }
+        }
+        Ok(Self {
+            path: path.ok_or_else(|| input.error("Missing `path` argument"))?,
+            variant: variant.ok_or_else(|| input.error("Missing `variant` argument"))?,
+            funcs,
+        })
+    }
+}
+
+enum SourceArg {
+    Path(syn::LitStr),
+    Variant(syn::LitStr),
+    Funcs(Vec<(syn::Ident, syn::LitStr)>),
+}
+
+impl syn::parse::Parse for SourceArg {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let ident: syn::Ident = input.parse()?;
+        match ident.to_string().as_str() {
+            "path" | "variant" => {
+                input.parse::<Token![=]>()?;
+                let lit = input.parse()?;
+                Ok(if ident == "path" {
+                    Self::Path(lit)
+                } else {
+                    Self::Variant(lit)
+                })
+            }
+            "funcs" => {
+                let content;
+                syn::parenthesized!(content in input);
+                let funcs = Punctuated::<FuncColumn, Token![,]>::parse_terminated(&content)?;
+                Ok(Self::Funcs(
+                    funcs
+                        .into_iter()
+                        .map(|FuncColumn(func, column)| (func, column))
+                        .collect(),
+                ))
+            }
+            _ => Err(Error::new_spanned(
+                &ident,
+                format!("Unknown `assoc_source` argument `{}`", ident),
+            )),
+        }
+    }
+}
+
+/// A function and its column, of form Ident = "column"
+struct FuncColumn(syn::Ident, syn::LitStr);
+
+impl syn::parse::Parse for FuncColumn {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let func = input.parse()?;
+        input.parse::<Token![=]>()?;
+        Ok(Self(func, input.parse()?))
+    }
+}
+
+/// A row of a data file: each column and its value, if any
+type Record = Vec<(String, Option<String>)>;
+
+/// Reads the data file and appends a variant for each of its rows to the enum
+pub(crate) fn expand(
+    args: SourceArgs,
+    mut ast: syn::DeriveInput,
+) -> Result<proc_macro2::TokenStream> {
+    let syn::Data::Enum(data) = &mut ast.data else {
+        return Err(Error::new_spanned(
+            &ast.ident,
+            "`assoc_source` is only applicable to enums",
+        ));
+    };
+    let funcs = ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(FUNC_ATTR))
+        .map(|attr| syn::parse2::<DeriveFuncs>(attr.meta.to_token_stream()))
+        .collect::<Result<Vec<DeriveFuncs>>>()?
+        .into_iter()
+        .flat_map(|DeriveFuncs(funcs)| funcs)
+        .collect::<Vec<DeriveFunc>>();
+    let path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default())
+        .join(args.path.value());
+    let records = std::fs::read_to_string(&path)
+        .map_err(|err| format!("Failed to read {}: {}", path.display(), err))
+        .and_then(|text| read_records(&path, &text))
+        .map_err(|err| Error::new_spanned(&args.path, err))?;
+    // A column may be absent from some rows of a JSON or TOML file, but not
+    // from all of them
+    for column in std::iter::once(&args.variant).chain(args.funcs.iter().map(|(_, column)| column))
+    {
+        if !records.is_empty()
+            && !records
+                .iter()
+                .any(|record| record.iter().any(|(key, _)| *key == column.value()))
+        {
+            return Err(Error::new_spanned(
+                column,
+                format!("Missing column `{}`", column.value()),
+            ));
+        }
+    }
+    let assoc_ident = syn::Ident::new(ASSOC_ATTR, proc_macro2::Span::call_site());
+    for (row, record) in records.iter().enumerate() {
+        let value = |column: &syn::LitStr| {
+            record
+                .iter()
+                .find(|(key, _)| *key == column.value())
+                .and_then(|(_, value)| value.as_deref())
+        };
+        let name = value(&args.variant).unwrap_or_default();
+        let var_ident = syn::parse_str::<syn::Ident>(name).map_err(|_| {
+            Error::new_spanned(
+                &args.variant,
+                format!("Invalid variant identifier `{}` in row {}", name, row + 1),
+            )
+        })?;
+        let mut attrs: Vec<syn::Attribute> = Vec::new();
+        for (func_ident, column) in &args.funcs {
+            let func = funcs
+                .iter()
+                .find(|func| func.sig.ident == *func_ident)
+                .ok_or_else(|| {
+                    Error::new_spanned(
+                        func_ident,
+                        format!("No function `{}` in a `func` attribute", func_ident),
+                    )
+                })?;
+            let Some(value) = value(column) else {
+                continue;
+            };
+            let tokens = build_value(func, value).map_err(|_| {
+                Error::new_spanned(
+                    column,
+                    format!(
+                        "Invalid value `{}` for {} in row {}",
+                        value,
+                        func_ident,
+                        row + 1
+                    ),
+                )
+            })?;
+            attrs.push(syn::parse_quote!(#[#assoc_ident(#func_ident = #tokens)]));
+        }
+        data.variants.push(syn::Variant {
+            attrs,
+            ident: var_ident,
+            fields: syn::Fields::Unit,
+            discriminant: None,
+        });
+    }
+    // Including the file makes the compiler rebuild when it changes
+    let path = path.to_string_lossy();
+    Ok(quote! {
+        #ast
+
+        const _: &[::core::primitive::u8] = ::core::include_bytes!(#path);
+    })
+}
+
+/// Converts a value into an expression (or pattern for reverse functions).
+/// Values for `&str` are string literals, and any other value is parsed as
+/// Rust syntax.
+fn build_value(func: &DeriveFunc, value: &str) -> Result<proc_macro2::TokenStream> {
+    let ty = if func.has_self() {
+        match &func.sig.output {
+            syn::ReturnType::Type(_, ty) => Some(&**ty),
+            syn::ReturnType::Default => None,
+        }
+    } else {
+        match func.sig.inputs.first() {
+            Some(syn::FnArg::Typed(pat_type)) => Some(&*pat_type.ty),
+            _ => None,
+        }
+    };
+    if ty.is_some_and(is_str) {
+        return Ok(quote!(#value));
+    }
+    if func.has_self() {
+        let expr: syn::Expr = syn::parse_str(value)?;
+        Ok(quote!(#expr))
+    } else {
+        let pat = syn::parse::Parser::parse_str(syn::Pat::parse_multi_with_leading_vert, value)?;
+        Ok(quote!(#pat))
+    }
+}
+
+/// Whether a type is `&str` or `Option<&str>`, with any lifetime
+fn is_str(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Reference(reference) => {
+            matches!(&*reference.elem, syn::Type::Path(path) if path.path.is_ident("str"))
+        }
+        syn::Type::Path(path) => path.path.segments.last().is_some_and(|segment| {
+            segment.ident == "Option"
+                && matches!(
+                    &segment.arguments,
+                    syn::PathArguments::AngleBracketed(args)
+                        if matches!(args.args.first(), Some(syn::GenericArgument::Type(ty)) if is_str(ty))
+                )
+        }),
+        _ => false,
+    }
+}
+
+fn read_records(path: &std::path::Path, text: &str) -> std::result::Result<Vec<Record>, String> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => read_csv(text),
+        Some("json") => read_json(text),
+        Some("toml") => read_toml(text),
+        _ => Err(format!(
+            "Unsupported file format for {}, expected a csv, json or toml file",
+            path.display()
+        )),
+    }
+}
+
+/// Reads a CSV file, where fields may be quoted and quotes within them are
+/// escaped by doubling
+fn read_csv(text: &str) -> std::result::Result<Vec<Record>, String> {
+    let mut lines: Vec<Vec<String>> = Vec::new();
+    let mut fields: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => (),
+            '\n' if !quoted => {
+                fields.push(std::mem::take(&mut field));
+                lines.push(std::mem::take(&mut fields));
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("Unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        lines.push(fields);
+    }
+    lines.retain(|line| line.len() > 1 || line.first().is_some_and(|field| !field.is_empty()));
+    let mut lines = lines.into_iter();
+    let header = lines.next().ok_or("Missing header row")?;
+    lines
+        .enumerate()
+        .map(|(row, line)| {
+            if line.len() != header.len() {
+                return Err(format!(
+                    "Expected {} fields in row {}, found {}",
+                    header.len(),
+                    row + 1,
+                    line.len()
+                ));
+            }
+            Ok(header
+                .iter()
+                .cloned()
+                .zip(line)
+                .map(|(key, value)| (key, Some(value).filter(|value| !value.is_empty())))
+                .collect())
+        })
+        .collect()
+}
+
+/// Reads a JSON file holding an array of flat objects
+fn read_json(text: &str) -> std::result::Result<Vec<Record>, String> {
+    let mut parser = JsonParser {
+        chars: text.chars().peekable(),
+    };
+    let records = parser.records()?;
+    parser.skip_whitespace();
+    match parser.chars.next() {
+        None => Ok(records),
+        Some(c) => Err(format!("Unexpected `{}` after the array", c)),
+    }
+}
+
+struct JsonParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl JsonParser<'_> {
+    fn records(&mut self) -> std::result::Result<Vec<Record>, String> {
+        let mut records = Vec::new();
+        self.expect('[')?;
+        while !self.end_of_list(']', records.is_empty())? {
+            let mut record = Vec::new();
+            self.expect('{')?;
+            while !self.end_of_list('}', record.is_empty())? {
+                self.skip_whitespace();
+                self.expect('"')?;
+                let key = self.string()?;
+                self.expect(':')?;
+                record.push((key, self.value()?));
+            }
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    /// Consumes the closing delimiter of a list, or the comma before its next
+    /// item
+    fn end_of_list(&mut self, close: char, first: bool) -> std::result::Result<bool, String> {
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&close) {
+            self.chars.next();
+            return Ok(true);
+        }
+        if !first {
+            self.expect(',')?;
+        }
+        Ok(false)
+    }
+
+    /// Parses a scalar value, which is `None` for `null`
+    fn value(&mut self) -> std::result::Result<Option<String>, String> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('"') => {
+                self.chars.next();
+                self.string().map(Some)
+            }
+            Some(c) if c.is_ascii_alphanumeric() || *c == '-' => {
+                let mut value = String::new();
+                while let Some(c) = self
+                    .chars
+                    .next_if(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.'))
+                {
+                    value.push(c);
+                }
+                Ok(Some(value).filter(|value| value != "null"))
+            }
+            Some(c) => Err(format!("Unsupported JSON value starting with `{}`", c)),
+            None => Err("Unexpected end of file".to_string()),
+        }
+    }
+
+    /// Parses the rest of a string, after its opening quote
+    fn string(&mut self) -> std::result::Result<String, String> {
+        let mut value = String::new();
+        loop {
+            match self.chars.next().ok_or("Unterminated string")? {
+                '"' => return Ok(value),
+                '\\' => match self.chars.next().ok_or("Unterminated string")? {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    'r' => value.push('\r'),
+                    'b' => value.push('\u{8}'),
+                    'f' => value.push('\u{c}'),
+                    'u' => {
+                        let mut code = self.hex_escape()?;
+                        if (0xd800..0xdc00).contains(&code) {
+                            self.expect('\\')?;
+                            self.expect('u')?;
+                            let low = self.hex_escape()?;
+                            if !(0xdc00..0xe000).contains(&low) {
+                                return Err(format!(
+                                    "Expected a low surrogate after `\\u{:04x}`, found `\\u{:04x}`",
+                                    code, low
+                                ));
+                            }
+                            code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                        }
+                        // Lone low surrogates are not characters, so they are
+                        // rejected here
+                        value.push(char::from_u32(code).ok_or("Invalid unicode escape")?);
+                    }
+                    c => value.push(c),
+                },
+                c => value.push(c),
+            }
+        }
+    }
+
+    fn hex_escape(&mut self) -> std::result::Result<u32, String> {
+        let hex = (0..4).filter_map(|_| self.chars.next()).collect::<String>();
+        u32::from_str_radix(&hex, 16).map_err(|_| format!("Invalid unicode escape `{}`", hex))
+    }
+
+    fn expect(&mut self, expected: char) -> std::result::Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("Expected `{}`, found `{}`", expected, c)),
+            None => Err(format!("Expected `{}`, found the end of file", expected)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+}
+
+/// Reads a TOML file holding a single array of tables, where each value is on
+/// its own line
+fn read_toml(text: &str) -> std::result::Result<Vec<Record>, String> {
+    let mut records: Vec<Record> = Vec::new();
+    let mut table: Option<&str> = None;
+    for (index, line) in text.lines().enumerate() {
+        let line_err = |msg: &str| format!("{} on line {}", msg, index + 1);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line
+            .strip_prefix("[[")
+            .and_then(|line| line.split_once("]]"))
+            .map(|(name, _)| name.trim())
+        {
+            if table.is_some_and(|table| table != name) {
+                return Err(line_err("Expected a single array of tables"));
+            }
+            table = Some(name);
+            records.push(Vec::new());
+            continue;
+        }
+        if line.starts_with('[') {
+            return Err(line_err("Expected an array of tables"));
+        }
+        let record = records
+            .last_mut()
+            .ok_or_else(|| line_err("Expected an array of tables before any values"))?;
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| line_err("Expected a key and value"))?;
+        let key = key.trim();
+        let key = key
+            .strip_prefix('"')
+            .and_then(|key| key.strip_suffix('"'))
+            .unwrap_or(key);
+        let value = toml_value(value.trim()).ok_or_else(|| line_err("Unsupported value"))?;
+        record.push((key.to_string(), Some(value)));
+    }
+    Ok(records)
+}
+
+/// Parses a TOML string, number or boolean, ignoring any trailing comment
+fn toml_value(value: &str) -> Option<String> {
+    let trailing = |rest: &str| {
+        let rest = rest.trim_start();
+        rest.is_empty() || rest.starts_with('#')
+    };
+    if let Some(rest) = value.strip_prefix('\'') {
+        let (value, rest) = rest.split_once('\'')?;
+        return trailing(rest).then(|| value.to_string());
+    }
+    if let Some(rest) = value.strip_prefix('"') {
+        let mut parsed = String::new();
+        let mut chars = rest.chars();
+        loop {
+            match chars.next()? {
+                '"' => return trailing(chars.as_str()).then_some(parsed),
+                '\\' => match chars.next()? {
+                    'n' => parsed.push('\n'),
+                    't' => parsed.push('\t'),
+                    'r' => parsed.push('\r'),
+                    'u' => {
+                        let hex = chars.by_ref().take(4).collect::<String>();
+                        parsed.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                    }
+                    c => parsed.push(c),
+                },
+                c => parsed.push(c),
+            }
+        }
+    }
+    let value = value.split('#').next()?.trim();
+    (!value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+' | '.')))
+    .then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Expands `assoc_source` with a file from `tests/data/malformed`
+    fn expand_malformed(file: &str) -> Result<proc_macro2::TokenStream> {
+        let path = format!("tests/data/malformed/{}", file);
+        let args = syn::parse_quote!(path = #path, variant = "name", funcs(code = "code"));
+        let ast = syn::parse_quote! {
+            #[func(pub fn code(&self) -> &'static str)]
+            enum Malformed {}
+        };
+        expand(args, ast)
+    }
+
+    #[test]
+    fn test_malformed_files() {
+        for (file, message) in [
+            (
+                "surrogate.json",
+                "Expected a low surrogate after `\\ud800`, found `\\u0041`",
+            ),
+            ("unterminated.csv", "Unterminated quoted field"),
+            ("value.toml", "Unsupported value on line 3"),
+        ] {
+            let err = expand_malformed(file).expect_err(file);
+            assert!(err.to_string().contains(message), "{}: {}", file, err);
+            let tokens = err.into_compile_error().to_string();
+            assert!(tokens.contains("compile_error"), "{}: {}", file, tokens);
+        }
+    }
+
+    #[test]
+    fn test_malformed_records() {
+        for (file, text) in [
+            ("a.json", ""),
+            ("a.json", "{}"),
+            ("a.json", "[{\"name\" \"Read\"}]"),
+            ("a.json", "[{\"name\": \"Read\""),
+            ("a.json", "[{\"name\": [1]}]"),
+            ("a.json", "[{\"name\": \"Read\"} {}]"),
+            ("a.json", "[] []"),
+            ("a.json", "[{\"name\": \"\\ud800\"}]"),
+            ("a.json", "[{\"name\": \"\\ud800\\udbff\"}]"),
+            ("a.json", "[{\"name\": \"\\udc00\"}]"),
+            ("a.json", "[{\"name\": \"\\uzzzz\"}]"),
+            ("a.json", "[{\"name\": \"\\u12"),
+            ("a.csv", ""),
+            ("a.csv", "name,code\nRead"),
+            ("a.csv", "name\n\"Read"),
+            ("a.toml", "name = \"Read\""),
+            ("a.toml", "[levels]"),
+            ("a.toml", "[[levels]]\n[[other]]"),
+            ("a.toml", "[[levels]]\nname"),
+            ("a.toml", "[[levels]]\nname = \"Read"),
+            ("a.toml", "[[levels]]\nname = \"\\ud800\""),
+            ("a.toml", "[[levels]]\nname = 'Read' code"),
+            ("a.yaml", "name: Read"),
+        ] {
+            let result = read_records(std::path::Path::new(file), text);
+            assert!(result.is_err(), "{} {:?}: {:?}", file, text, result);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! State machines, declared with `#[assoc_state(Event)]` on the enum and
+//! `#[assoc(on(Event::Start) = Running)]` on each state. The transitions of
+//! every state are checked when deriving: their targets must be unit variants
+//! of the enum, each event may only have one transition per state, and states
+//! declared terminal, as in `#[assoc_state(Event, terminal(Done))]`, must not
+//! have any.
+
+use quote::quote;
+use syn::{Error, Result, Token, Variant, parenthesized, punctuated::Punctuated};
+
+use crate::{ASSOC_STATE_ATTR, Association, AssociationType, scrutinee};
+
+/// The name transitions are given under by default
+const DEFAULT_KEY: &str = "on";
+
+/// A state machine declared within an `assoc_state` attribute
+struct StateDecl {
+    event: syn::Type,
+    /// The name transitions are given under, `on` unless set with `on = ..`
+    key: syn::Ident,
+    terminal: Vec<syn::Ident>,
+}
+
+impl syn::parse::Parse for StateDecl {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let event = input.parse()?;
+        let mut decl = Self {
+            event,
+            key: syn::Ident::new(DEFAULT_KEY, input.span()),
+            terminal: Vec::new(),
+        };
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let option: syn::Ident = input.parse()?;
+            match option.to_string().as_str() {
+                "on" => {
+                    input.parse::<Token![=]>()?;
+                    decl.key = input.parse()?;
+                }
+                "terminal" => {
+                    let content;
+                    parenthesized!(content in input);
+                    decl.terminal
+                        .extend(Punctuated::<syn::Ident, Token![,]>::parse_terminated(
+                            &content,
+                        )?);
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        &option,
+                        format!("Unknown `assoc_state` option `{}`", option),
+                    ));
+                }
+            }
+        }
+        Ok(decl)
+    }
+}
+
+/// Builds the transition functions of a state machine. The result is placed
+/// within the same `impl` block as the associated functions.
+pub(crate) fn build_state(
+    ast: &syn::DeriveInput,
+    variants: &[&Variant],
+) -> Result<proc_macro2::TokenStream> {
+    let Some(attr) = ast
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(ASSOC_STATE_ATTR))
+    else {
+        return Ok(quote!());
+    };
+    let decl: StateDecl = attr.parse_args()?;
+    let key = &decl.key;
+    for state in &decl.terminal {
+        find_state(variants, state)?;
+    }
+    let mut transitions = Vec::new();
+    let mut events = Vec::new();
+    for variant in variants {
+        let var_ident = &variant.ident;
+        let mut arms = Vec::new();
+        let mut state_events: Vec<(String, syn::Pat)> = Vec::new();
+        for assoc in Association::get_variant_assocs(variant, false) {
+            if assoc.func != *key {
+                continue;
+            }
+            let (pat, target) = match assoc.assoc {
+                AssociationType::Keyed(pat, target) => (pat, target),
+                AssociationType::Forward(expr) => {
+                    return Err(Error::new_spanned(
+                        expr,
+                        format!("Transitions are of form `{}(Event::Variant) = State`", key),
+                    ));
+                }
+                _ => continue,
+            };
+            if decl.terminal.contains(var_ident) {
+                return Err(Error::new_spanned(
+                    pat,
+                    format!("`{}` is terminal and cannot have transitions", var_ident),
+                ));
+            }
+            for case in scrutinee::pat_cases(&pat) {
+                if !matches!(case, syn::Pat::Path(_) | syn::Pat::Lit(_)) {
+                    return Err(Error::new_spanned(
+                        case,
+                        "Transitions must be keyed by a variant of the event enum, eg. `Event::Start`, or a literal",
+                    ));
+                }
+                let tokens = quote!(#case).to_string();
+                if state_events.iter().any(|(other, _)| *other == tokens) {
+                    return Err(Error::new_spanned(
+                        case,
+                        format!(
+                            "`{}` already has a transition from `{}`",
+                            tokens.replace(' ', ""),
+                            var_ident
+                        ),
+                    ));
+                }
+                state_events.push((tokens, case.clone()));
+            }
+            let target = target_state(variants, &target)?;
+            arms.push(quote!(#pat => ::core::option::Option::Some(Self::#target),));
+        }
+        let pats = state_events.into_iter().map(|(_, pat)| pat);
+        events.push(quote!(Self::#var_ident { .. } => &[#(#pats),*],));
+        if !arms.is_empty() {
+            transitions.push(quote! {
+                Self::#var_ident { .. } => match event {
+                    #(#arms)*
+                    _ => ::core::option::Option::None,
+                },
+            });
+        }
+    }
+    let vis = &ast.vis;
+    let event = &decl.event;
+    let is_terminal = if decl.terminal.is_empty() {
+        quote!(false)
+    } else {
+        let terminal = &decl.terminal;
+        quote!(::core::matches!(self, #(Self::#terminal { .. })|*))
+    };
+    Ok(quote! {
+        /// Returns the state this state moves to on the given event, or `None`
+        /// if the event is not valid in this state
+        #[allow(unreachable_patterns)]
+        #vis const fn transition(&self, event: &#event) -> ::core::option::Option<Self> {
+            match self {
+                #(#transitions)*
+                _ => ::core::option::Option::None,
+            }
+        }
+
+        /// Returns the events which are valid in this state, in declaration
+        /// order
+        #vis const fn events(&self) -> &'static [#event] {
+            match self {
+                #(#events)*
+            }
+        }
+
+        /// Whether this state is declared terminal, having no transitions
+        #vis const fn is_terminal(&self) -> bool {
+            #is_terminal
+        }
+    })
+}
+
+/// Finds the variant of a state named in the attribute
+fn find_state<'a>(variants: &[&'a Variant], state: &syn::Ident) -> Result<&'a Variant> {
+    variants
+        .iter()
+        .find(|variant| variant.ident == *state)
+        .copied()
+        .ok_or_else(|| Error::new_spanned(state, format!("Unknown state `{}`", state)))
+}
+
+/// Resolves the target of a transition, which is a unit variant named either
+/// `State` or `Self::State`
+fn target_state<'a>(variants: &[&'a Variant], target: &syn::Expr) -> Result<&'a syn::Ident> {
+    let ident = match target {
+        syn::Expr::Path(expr) if expr.qself.is_none() => match expr.path.segments.len() {
+            1 => Some(&expr.path.segments[0].ident),
+            2 if expr.path.segments[0].ident == "Self" => Some(&expr.path.segments[1].ident),
+            _ => None,
+        },
+        _ => None,
+    }
+    .ok_or_else(|| Error::new_spanned(target, "Expected a state, eg. `Running`"))?;
+    let variant = find_state(variants, ident)?;
+    if !matches!(variant.fields, syn::Fields::Unit) {
+        return Err(Error::new_spanned(
+            target,
+            format!("`{}` must be a unit variant to be a target", ident),
+        ));
+    }
+    Ok(&variant.ident)
+}
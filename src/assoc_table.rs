@@ -0,0 +1,221 @@
+//! Maps keyed by variant, enabled with `#[assoc_table]` on the enum. This
+//! generates a companion type named `{EnumName}Map<V>`, or the name given as in
+//! `#[assoc_table(Limits)]`, backed by an array with a slot for every variant.
+//! The attribute is not named `assoc_map`, which maps between enums.
+
+use quote::{format_ident, quote};
+use syn::{Error, Result, Variant, ext::IdentExt};
+
+use crate::ASSOC_TABLE_ATTR;
+
+/// Builds the map type of an enum, which is placed alongside the enum
+pub(crate) fn build_table(
+    ast: &syn::DeriveInput,
+    variants: &[&Variant],
+) -> Result<proc_macro2::TokenStream> {
+    let Some(attr) = ast
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(ASSOC_TABLE_ATTR))
+    else {
+        return Ok(quote!());
+    };
+    let name = &ast.ident;
+    let map_ident = match &attr.meta {
+        syn::Meta::Path(_) => format_ident!("{}Map", name),
+        _ => attr.parse_args()?,
+    };
+    if !ast.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            attr,
+            "`assoc_table` is not supported for generic enums",
+        ));
+    }
+    if let Some(variant) = variants
+        .iter()
+        .find(|variant| !matches!(variant.fields, syn::Fields::Unit))
+    {
+        return Err(Error::new_spanned(
+            variant,
+            "`assoc_table` requires every variant to be a unit variant",
+        ));
+    }
+    if variants.is_empty() {
+        return Err(Error::new_spanned(
+            attr,
+            "`assoc_table` requires at least one variant",
+        ));
+    }
+    let vis = &ast.vis;
+    let count = variants.len();
+    let var_idents = variants
+        .iter()
+        .map(|variant| &variant.ident)
+        .collect::<Vec<_>>();
+    let var_names = var_idents.iter().map(|ident| ident.unraw().to_string());
+    let indices = (0..count).collect::<Vec<usize>>();
+    let doc = format!(
+        "A map from every [`{}`] variant to a value, stored as an array",
+        name
+    );
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::hash::Hash,
+        )]
+        #vis struct #map_ident<V> {
+            values: [V; #count],
+        }
+
+        #[allow(dead_code)]
+        impl<V> #map_ident<V> {
+            /// The number of variants, which is the number of values
+            #vis const LEN: usize = #count;
+
+            /// Returns the map holding the values of an array, where the value
+            /// at index `n` belongs to the variant at index `n`
+            #vis const fn from_array(values: [V; #count]) -> Self {
+                Self { values }
+            }
+
+            /// Returns the map holding the value of a function for each
+            /// variant, eg. `from_assoc(Enum::default_limit)`
+            #vis fn from_assoc(mut f: impl FnMut(&#name) -> V) -> Self {
+                Self {
+                    values: ::core::array::from_fn(|index| f(&Self::key(index))),
+                }
+            }
+
+            const fn slot(key: &#name) -> usize {
+                match key {
+                    #(#name::#var_idents => #indices,)*
+                }
+            }
+
+            const fn key(index: usize) -> #name {
+                match index {
+                    #(#indices => #name::#var_idents,)*
+                    _ => ::core::unreachable!(),
+                }
+            }
+
+            /// Returns the value of the variant
+            #vis const fn get(&self, key: &#name) -> &V {
+                &self.values[Self::slot(key)]
+            }
+
+            /// Returns the value of the variant mutably
+            #vis fn get_mut(&mut self, key: &#name) -> &mut V {
+                &mut self.values[Self::slot(key)]
+            }
+
+            /// Sets the value of the variant, returning the previous one
+            #vis fn insert(&mut self, key: #name, value: V) -> V {
+                ::core::mem::replace(self.get_mut(&key), value)
+            }
+
+            /// Returns an iterator over the variants and their values, in
+            /// declaration order
+            #vis fn iter(&self) -> impl ::core::iter::Iterator<Item = (#name, &V)> {
+                self.values
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| (Self::key(index), value))
+            }
+
+            /// Returns an iterator over the variants and their mutable values,
+            /// in declaration order
+            #vis fn iter_mut(
+                &mut self,
+            ) -> impl ::core::iter::Iterator<Item = (#name, &mut V)> {
+                self.values
+                    .iter_mut()
+                    .enumerate()
+                    .map(|(index, value)| (Self::key(index), value))
+            }
+
+            /// Returns an iterator over the values, in declaration order of
+            /// their variants
+            #vis fn values(&self) -> ::core::slice::Iter<'_, V> {
+                self.values.iter()
+            }
+
+            /// Returns an iterator over the mutable values, in declaration
+            /// order of their variants
+            #vis fn values_mut(&mut self) -> ::core::slice::IterMut<'_, V> {
+                self.values.iter_mut()
+            }
+
+            /// Returns the values as an array
+            #vis const fn as_array(&self) -> &[V; #count] {
+                &self.values
+            }
+
+            /// Returns the values as an array, consuming the map
+            #vis fn into_array(self) -> [V; #count] {
+                self.values
+            }
+
+            /// Returns a map with a function applied to every value
+            #vis fn map<U>(self, mut f: impl FnMut(#name, V) -> U) -> #map_ident<U> {
+                let mut index = 0;
+                #map_ident {
+                    values: self.values.map(|value| {
+                        let key = Self::key(index);
+                        index += 1;
+                        f(key, value)
+                    }),
+                }
+            }
+        }
+
+        impl<V: ::core::default::Default> ::core::default::Default for #map_ident<V> {
+            fn default() -> Self {
+                Self::from_assoc(|_| V::default())
+            }
+        }
+
+        impl<V> ::core::ops::Index<#name> for #map_ident<V> {
+            type Output = V;
+
+            fn index(&self, key: #name) -> &V {
+                self.get(&key)
+            }
+        }
+
+        impl<V> ::core::ops::IndexMut<#name> for #map_ident<V> {
+            fn index_mut(&mut self, key: #name) -> &mut V {
+                self.get_mut(&key)
+            }
+        }
+
+        impl<V> ::core::ops::Index<&#name> for #map_ident<V> {
+            type Output = V;
+
+            fn index(&self, key: &#name) -> &V {
+                self.get(key)
+            }
+        }
+
+        impl<V> ::core::ops::IndexMut<&#name> for #map_ident<V> {
+            fn index_mut(&mut self, key: &#name) -> &mut V {
+                self.get_mut(key)
+            }
+        }
+
+        impl<V: ::core::fmt::Debug> ::core::fmt::Debug for #map_ident<V> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                const NAMES: [&str; #count] = [#(#var_names),*];
+                let mut map = f.debug_map();
+                for (name, value) in NAMES.iter().zip(&self.values) {
+                    map.entry(&::core::format_args!("{}", name), value);
+                }
+                map.finish()
+            }
+        }
+    })
+}
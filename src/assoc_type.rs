@@ -0,0 +1,141 @@
+//! Type associations, declared with `#[assoc_type(Name)]` on the enum and
+//! `#[assoc(Name = Type)]` on each variant. Every variant gets a zero-sized
+//! marker type which implements a generated trait holding its types.
+
+use quote::{ToTokens, format_ident, quote};
+use syn::{Error, Result, Token, Variant, punctuated::Punctuated};
+
+use crate::{ASSOC_TYPE_ATTR, Association, AssociationType, to_snake_case};
+
+/// An associated type declared within an `assoc_type` attribute, of form Ident
+/// or Ident = Type where the type is the default for variants without an
+/// association
+struct AssocTypeDecl {
+    ident: syn::Ident,
+    def: Option<syn::Type>,
+}
+
+impl syn::parse::Parse for AssocTypeDecl {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let ident = input.parse()?;
+        let def = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { ident, def })
+    }
+}
+
+/// Builds the module holding the marker types and their trait, along with the
+/// trait implementations. These are placed outside of the module so that
+/// associated types resolve in the scope of the enum.
+pub(crate) fn build_assoc_types(
+    ast: &syn::DeriveInput,
+    variants: &[&Variant],
+) -> Result<proc_macro2::TokenStream> {
+    let decls = ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(ASSOC_TYPE_ATTR))
+        .map(|attr| attr.parse_args_with(Punctuated::<AssocTypeDecl, Token![,]>::parse_terminated))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<AssocTypeDecl>>();
+    if decls.is_empty() {
+        return Ok(quote!());
+    }
+    let vis = &ast.vis;
+    let name = &ast.ident;
+    let mod_ident = format_ident!("{}_types", to_snake_case(&name.to_string()));
+    let mod_doc = format!("Marker types for the variants of [`{0}`](super::{0})", name);
+    let trait_doc = format!(
+        "Types associated with a variant of [`{0}`](super::{0})",
+        name
+    );
+    let type_idents = decls.iter().map(|decl| &decl.ident);
+    let markers = variants.iter().map(|variant| {
+        let var_ident = &variant.ident;
+        let doc = format!(
+            "Marker type for [`{0}::{1}`](super::{0}::{1})",
+            name, var_ident
+        );
+        quote! {
+            #[doc = #doc]
+            #[derive(
+                ::core::clone::Clone,
+                ::core::marker::Copy,
+                ::core::fmt::Debug,
+                ::core::default::Default,
+                ::core::cmp::PartialEq,
+                ::core::cmp::Eq,
+                ::core::hash::Hash,
+            )]
+            pub struct #var_ident;
+        }
+    });
+    let impls = variants
+        .iter()
+        .map(|variant| {
+            let var_ident = &variant.ident;
+            let types = decls
+                .iter()
+                .map(|decl| {
+                    let ident = &decl.ident;
+                    let ty = variant_type(variant, decl)?;
+                    Ok(quote!(type #ident = #ty;))
+                })
+                .collect::<Result<Vec<proc_macro2::TokenStream>>>()?;
+            Ok(quote! {
+                impl #mod_ident::Types for #mod_ident::#var_ident {
+                    #(#types)*
+                }
+            })
+        })
+        .collect::<Result<Vec<proc_macro2::TokenStream>>>()?;
+    Ok(quote! {
+        #[doc = #mod_doc]
+        #[allow(dead_code)]
+        #vis mod #mod_ident {
+            #[doc = #trait_doc]
+            pub trait Types {
+                #(type #type_idents;)*
+            }
+
+            #(#markers)*
+        }
+
+        #(#impls)*
+    })
+}
+
+/// The type a variant associates with a declared type. Simple types such as
+/// `i64` are valid expressions and are parsed as forward associations, so
+/// those are converted here.
+fn variant_type(variant: &Variant, decl: &AssocTypeDecl) -> Result<syn::Type> {
+    let mut types = Association::get_variant_assocs(variant, false)
+        .filter(|assoc| assoc.func == decl.ident)
+        .filter_map(|assoc| match assoc.assoc {
+            AssociationType::Type(ty) => Some(Ok(ty)),
+            AssociationType::Forward(expr) => {
+                Some(syn::parse2::<syn::Type>(expr.into_token_stream()))
+            }
+            _ => None,
+        })
+        .collect::<Result<Vec<syn::Type>>>()?;
+    match types.len() {
+        0 => decl.def.clone().ok_or_else(|| {
+            Error::new_spanned(
+                variant,
+                format!("Missing `assoc` attribute for {}", decl.ident),
+            )
+        }),
+        1 => Ok(types.remove(0)),
+        _ => Err(Error::new_spanned(
+            variant,
+            format!("Too many `assoc` attributes for {}", decl.ident),
+        )),
+    }
+}
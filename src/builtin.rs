@@ -0,0 +1,209 @@
+//! Built-in functions, which are enabled with `#[assoc_builtin(...)]` on the
+//! enum. Rather than from associations, these are generated from the variants
+//! themselves.
+
+use quote::{format_ident, quote};
+use syn::{Error, Result, Token, Variant, ext::IdentExt, punctuated::Punctuated};
+
+use crate::ASSOC_BUILTIN_ATTR;
+
+const INT_REPRS: [&str; 12] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// A built-in function enabled within an `assoc_builtin` attribute, of form
+/// Ident or Ident = Ident to give the function another name
+struct BuiltinDecl {
+    kind: syn::Ident,
+    name: syn::Ident,
+}
+
+impl syn::parse::Parse for BuiltinDecl {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let kind: syn::Ident = input.parse()?;
+        let name = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            input.parse()?
+        } else {
+            kind.clone()
+        };
+        Ok(Self { kind, name })
+    }
+}
+
+/// Builds every built-in function enabled for an enum. The result is placed
+/// within the same `impl` block as the associated functions.
+pub(crate) fn build_builtins(
+    ast: &syn::DeriveInput,
+    variants: &[&Variant],
+) -> Result<proc_macro2::TokenStream> {
+    let decls = ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(ASSOC_BUILTIN_ATTR))
+        .map(|attr| attr.parse_args_with(Punctuated::<BuiltinDecl, Token![,]>::parse_terminated))
+        .collect::<Result<Vec<_>>>()?;
+    decls
+        .into_iter()
+        .flatten()
+        .map(|decl| {
+            let vis = &ast.vis;
+            let name = &decl.name;
+            match decl.kind.to_string().as_str() {
+                "discriminant" => {
+                    let repr = repr_type(ast);
+                    let arms = variants.iter().zip(discriminants(variants)).map(
+                        |(variant, discriminant)| {
+                            let var_ident = &variant.ident;
+                            quote!(Self::#var_ident { .. } => #discriminant,)
+                        },
+                    );
+                    Ok(quote! {
+                        /// Returns the discriminant of this variant
+                        #vis const fn #name(&self) -> #repr {
+                            match self {
+                                #(#arms)*
+                            }
+                        }
+                    })
+                }
+                "from_discriminant" => {
+                    let repr = repr_type(ast);
+                    let (consts, arms): (Vec<_>, Vec<_>) = variants
+                        .iter()
+                        .zip(discriminants(variants))
+                        .enumerate()
+                        .filter(|(_, (variant, _))| matches!(variant.fields, syn::Fields::Unit))
+                        .map(|(index, (variant, discriminant))| {
+                            let var_ident = &variant.ident;
+                            // Named by index, as names derived from the variant may collide
+                            let const_ident = format_ident!("__D{}", index);
+                            (
+                                quote!(const #const_ident: #repr = #discriminant;),
+                                quote! {
+                                    #const_ident => ::core::option::Option::Some(Self::#var_ident),
+                                },
+                            )
+                        })
+                        .unzip();
+                    Ok(quote! {
+                        /// Returns the unit variant with the given discriminant, if any
+                        #vis const fn #name(discriminant: #repr) -> ::core::option::Option<Self> {
+                            #(#consts)*
+                            match discriminant {
+                                #(#arms)*
+                                _ => ::core::option::Option::None,
+                            }
+                        }
+                    })
+                }
+                "variant_name" => {
+                    let arms = variants.iter().map(|variant| {
+                        let var_ident = &variant.ident;
+                        let var_name = var_ident.unraw().to_string();
+                        quote!(Self::#var_ident { .. } => #var_name,)
+                    });
+                    Ok(quote! {
+                        /// Returns the name of this variant
+                        #vis const fn #name(&self) -> &'static str {
+                            match self {
+                                #(#arms)*
+                            }
+                        }
+                    })
+                }
+                "index" => {
+                    let arms = variants.iter().enumerate().map(|(index, variant)| {
+                        let var_ident = &variant.ident;
+                        quote!(Self::#var_ident { .. } => #index,)
+                    });
+                    Ok(quote! {
+                        /// Returns the index of this variant in declaration order
+                        #vis const fn #name(&self) -> usize {
+                            match self {
+                                #(#arms)*
+                            }
+                        }
+                    })
+                }
+                "from_index" => {
+                    let arms = variants
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, variant)| matches!(variant.fields, syn::Fields::Unit))
+                        .map(|(index, variant)| {
+                            let var_ident = &variant.ident;
+                            quote!(#index => ::core::option::Option::Some(Self::#var_ident),)
+                        });
+                    Ok(quote! {
+                        /// Returns the unit variant at the given index in declaration
+                        /// order, if any
+                        #vis const fn #name(index: usize) -> ::core::option::Option<Self> {
+                            match index {
+                                #(#arms)*
+                                _ => ::core::option::Option::None,
+                            }
+                        }
+                    })
+                }
+                "count" => {
+                    // Named `COUNT` unless renamed
+                    let name = if decl.kind == decl.name {
+                        format_ident!("COUNT", span = decl.kind.span())
+                    } else {
+                        decl.name.clone()
+                    };
+                    let count = variants.len();
+                    Ok(quote! {
+                        /// The number of variants
+                        #vis const #name: usize = #count;
+                    })
+                }
+                _ => Err(Error::new_spanned(
+                    &decl.kind,
+                    format!("Unknown built-in function `{}`", decl.kind),
+                )),
+            }
+        })
+        .collect()
+}
+
+/// The integer type given in the enum's `repr` attribute, or `isize` which is
+/// the default type of discriminants
+fn repr_type(ast: &syn::DeriveInput) -> syn::Ident {
+    ast.attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<syn::Ident, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .find(|ident| INT_REPRS.iter().any(|repr| ident == repr))
+        .unwrap_or_else(|| format_ident!("isize"))
+}
+
+/// The discriminant of each variant as a constant expression. Variants without
+/// an explicit discriminant are one more than the previous variant, starting
+/// from zero.
+pub(crate) fn discriminants(variants: &[&Variant]) -> Vec<proc_macro2::TokenStream> {
+    let mut base: Option<&syn::Expr> = None;
+    let mut offset = 0usize;
+    variants
+        .iter()
+        .map(|variant| {
+            if let Some((_, expr)) = &variant.discriminant {
+                base = Some(expr);
+                offset = 0;
+            }
+            let literal = proc_macro2::Literal::usize_unsuffixed(offset);
+            let discriminant = match base {
+                Some(expr) if offset == 0 => quote!((#expr)),
+                Some(expr) => quote!((#expr) + #literal),
+                None => quote!(#literal),
+            };
+            offset += 1;
+            discriminant
+        })
+        .collect()
+}
@@ -0,0 +1,71 @@
+//! Dumps the generated code of an enum for debugging, enabled with the
+//! `#[assoc_debug]` attribute or the `ENUM_ASSOC_DEBUG` environment variable.
+//! The variable holds a comma-separated list of enum names, or `*` for every
+//! enum. The expansion is written to `enum-assoc/{EnumName}.rs` under the
+//! target directory, formatted with `prettyplease`.
+
+use std::path::PathBuf;
+
+use syn::{Error, Result};
+
+use crate::ASSOC_DEBUG_ATTR;
+
+const DEBUG_ENV: &str = "ENUM_ASSOC_DEBUG";
+
+/// Writes the expansion of an enum to a file if debugging is enabled for it
+pub(crate) fn dump(ast: &syn::DeriveInput, expansion: &proc_macro2::TokenStream) -> Result<()> {
+    let name = ast.ident.to_string();
+    let attr = ast
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(ASSOC_DEBUG_ATTR));
+    let from_env = std::env::var(DEBUG_ENV).is_ok_and(|names| {
+        names
+            .split(',')
+            .map(str::trim)
+            .any(|debug_name| debug_name == "*" || debug_name == name)
+    });
+    if attr.is_none() && !from_env {
+        return Ok(());
+    }
+    let dir = target_dir().join("enum-assoc");
+    let path = dir.join(format!("{}.rs", name));
+    std::fs::create_dir_all(&dir)
+        .and_then(|()| std::fs::write(&path, render(ast, expansion)))
+        .map_err(|err| {
+            let msg = format!("Failed to write {}: {}", path.display(), err);
+            match attr {
+                Some(attr) => Error::new_spanned(attr, msg),
+                None => Error::new_spanned(&ast.ident, msg),
+            }
+        })
+}
+
+/// The target directory of the build, which is `CARGO_TARGET_DIR` if set.
+/// Otherwise, it is the closest `target` directory above the crate being
+/// compiled, so that workspace members share the workspace's target directory.
+fn target_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("CARGO_TARGET_DIR") {
+        return PathBuf::from(dir);
+    }
+    let manifest_dir = PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default());
+    manifest_dir
+        .ancestors()
+        .map(|dir| dir.join("target"))
+        .find(|dir| dir.join("CACHEDIR.TAG").exists())
+        .unwrap_or_else(|| manifest_dir.join("target"))
+}
+
+/// The contents of the file an expansion is written to. Expansions which do
+/// not parse as a file, which only happens with invalid code in associations,
+/// are written as plain tokens.
+pub(crate) fn render(ast: &syn::DeriveInput, expansion: &proc_macro2::TokenStream) -> String {
+    let code = match syn::parse2::<syn::File>(expansion.clone()) {
+        Ok(file) => prettyplease::unparse(&file),
+        Err(_) => format!("{}\n", expansion),
+    };
+    format!(
+        "// Expansion of #[derive(Assoc)] for {}\n\n{}",
+        ast.ident, code
+    )
+}
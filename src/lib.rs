@@ -1,19 +1,108 @@
 #![doc = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/README.md"))]
 
 use proc_macro::TokenStream;
-use quote::{ToTokens, quote};
+use quote::{ToTokens, quote, quote_spanned};
 use syn::{
-    Error, FnArg, Result, Token, Variant, parenthesized, parse::Parser, punctuated::Punctuated,
+    Error, FnArg, Result, Token, Variant,
+    ext::IdentExt,
+    parenthesized,
+    parse::{Parser, discouraged::Speculative},
+    punctuated::Punctuated,
     spanned::Spanned,
 };
 
+mod assoc_cmp;
+mod assoc_enum;
+mod assoc_error;
+mod assoc_macro;
+mod assoc_map;
+mod assoc_set;
+mod assoc_source;
+mod assoc_state;
+mod assoc_table;
+mod assoc_type;
+mod builtin;
+mod debug;
+mod lookup;
+mod meta;
+mod multi;
+mod placeholder;
+mod scrutinee;
+
 const FUNC_ATTR: &str = "func";
 const ASSOC_ATTR: &str = "assoc";
+const ASSOC_TYPE_ATTR: &str = "assoc_type";
+const ASSOC_BUILTIN_ATTR: &str = "assoc_builtin";
+const ASSOC_DEBUG_ATTR: &str = "assoc_debug";
+const ASSOC_META_ATTR: &str = "assoc_meta";
+const ASSOC_MAP_ATTR: &str = "assoc_map";
+const ASSOC_SET_ATTR: &str = "assoc_set";
+const ASSOC_TABLE_ATTR: &str = "assoc_table";
+const ASSOC_ORD_ATTR: &str = "assoc_ord";
+const ASSOC_EQ_ATTR: &str = "assoc_eq";
+const ASSOC_ERROR_ATTR: &str = "assoc_error";
+const ASSOC_STATE_ATTR: &str = "assoc_state";
+const ASSOC_MACRO_ATTR: &str = "assoc_macro";
+/// Every helper attribute of the derive macro, which must be kept in sync with
+/// the `attributes` of `derive_assoc`
+const HELPER_ATTRS: [&str; 14] = [
+    FUNC_ATTR,
+    ASSOC_ATTR,
+    ASSOC_TYPE_ATTR,
+    ASSOC_BUILTIN_ATTR,
+    ASSOC_DEBUG_ATTR,
+    ASSOC_META_ATTR,
+    ASSOC_MAP_ATTR,
+    ASSOC_SET_ATTR,
+    ASSOC_TABLE_ATTR,
+    ASSOC_ORD_ATTR,
+    ASSOC_EQ_ATTR,
+    ASSOC_ERROR_ATTR,
+    ASSOC_STATE_ATTR,
+    ASSOC_MACRO_ATTR,
+];
 
-#[proc_macro_derive(Assoc, attributes(func, assoc))]
+#[proc_macro_derive(
+    Assoc,
+    attributes(
+        func,
+        assoc,
+        assoc_type,
+        assoc_builtin,
+        assoc_debug,
+        assoc_meta,
+        assoc_map,
+        assoc_set,
+        assoc_table,
+        assoc_ord,
+        assoc_eq,
+        assoc_error,
+        assoc_state,
+        assoc_macro
+    )
+)]
 pub fn derive_assoc(input: TokenStream) -> TokenStream {
     impl_macro(&syn::parse(input).expect("Failed to parse macro input"))
-        //.map(|t| {println!("{}", quote!(#t)); t})
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Defines an enum along with its associations as a table. See the crate
+/// documentation for the syntax.
+#[proc_macro]
+pub fn assoc_enum(input: TokenStream) -> TokenStream {
+    syn::parse::<assoc_enum::AssocEnum>(input)
+        .and_then(|assoc_enum| assoc_enum.expand())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Reads variants and their associations from a data file. See the crate
+/// documentation for the arguments.
+#[proc_macro_attribute]
+pub fn assoc_source(attr: TokenStream, item: TokenStream) -> TokenStream {
+    syn::parse::<assoc_source::SourceArgs>(attr)
+        .and_then(|args| assoc_source::expand(args, syn::parse(item)?))
         .unwrap_or_else(syn::Error::into_compile_error)
         .into()
 }
@@ -33,8 +122,9 @@ fn impl_macro(ast: &syn::DeriveInput) -> Result<proc_macro2::TokenStream> {
     } else {
         panic!("#[derive(Assoc)] only applicable to enums")
     };
+    check_const_collisions(&variants, &fns)?;
     let functions: Vec<proc_macro2::TokenStream> = fns
-        .into_iter()
+        .iter()
         .flat_map(|DeriveFuncs(funcs)| {
             funcs
                 .iter()
@@ -42,13 +132,37 @@ fn impl_macro(ast: &syn::DeriveInput) -> Result<proc_macro2::TokenStream> {
                 .collect::<Vec<_>>()
         })
         .collect::<Result<Vec<proc_macro2::TokenStream>>>()?;
-    Ok(quote! {
+    let builtins = builtin::build_builtins(ast, &variants)?;
+    let maps = assoc_map::build_maps(ast, &variants)?;
+    let state = assoc_state::build_state(ast, &variants)?;
+    let assoc_types = assoc_type::build_assoc_types(ast, &variants)?;
+    let (meta, meta_mod) = meta::build_meta(ast, &variants, &fns)?;
+    let set = assoc_set::build_set(ast, &variants)?;
+    let table = assoc_table::build_table(ast, &variants)?;
+    let cmp = assoc_cmp::build_cmp(ast, &variants)?;
+    let error = assoc_error::build_error(ast, &variants, &fns)?;
+    let assoc_macro = assoc_macro::build_macro(ast, &variants, &fns)?;
+    let expansion = quote! {
         #[allow(clippy::used_underscore_binding)]
         impl <#generic_params> #name #generics
         {
+            #builtins
+            #maps
+            #state
             #(#functions)*
+            #meta
         }
-    })
+
+        #assoc_types
+        #meta_mod
+        #set
+        #table
+        #cmp
+        #error
+        #assoc_macro
+    };
+    debug::dump(ast, &expansion)?;
+    Ok(expansion)
 }
 
 fn build_function(
@@ -59,32 +173,63 @@ fn build_function(
     let vis = &func.vis;
     let sig = &func.sig;
     // has_self determines whether or not this a reverse assoc
-    let has_self = match func.sig.inputs.first() {
-        Some(FnArg::Receiver(_)) => true,
-        Some(FnArg::Typed(pat_type)) => {
-            let pat = &pat_type.pat;
-            quote!(#pat).to_string().trim() == "self"
-        }
-        None => false,
+    let has_self = func.has_self();
+    // Matches `Option<T>` by its last segment, so that `::core::option::Option<T>` counts
+    let is_option = match &func.sig.output {
+        syn::ReturnType::Type(_, ty) => option_inner(ty).is_some(),
+        syn::ReturnType::Default => false,
     };
-    let is_option = if let syn::ReturnType::Type(_, ty) = &func.sig.output {
-        let s = quote!(#ty).to_string();
-        let trimmed = s.trim();
-        trimmed.starts_with("Option") && trimmed.len() > 6 && trimmed[6..].trim().starts_with("<")
+    if func.options.is_static && !has_self {
+        return Err(func.error("`static` is only supported for forward associations"));
+    }
+    if func.options.is_static && func.options.consts {
+        return Err(func.error("`static` and `consts` cannot be combined"));
+    }
+    let associated_funcs = associated_funcs
+        .iter()
+        .map(|func| func.sig.ident.clone())
+        .collect::<Vec<syn::Ident>>();
+    if has_self
+        && (func.options.lookup.is_some()
+            || func.options.scrutinee.is_some()
+            || scrutinee::normalizes(&func.options))
+    {
+        return Err(func.error(
+            "`lookup`, `match`, `trim` and `ascii_case_insensitive` are only supported for reverse associations",
+        ));
+    }
+    let multi = if has_self {
+        None
     } else {
-        false
+        multi::Multi::of(&func.sig)?
     };
+    if !has_self {
+        let keyed = variants
+            .iter()
+            .flat_map(|variant| Association::get_variant_assocs(variant, true))
+            .filter(|assoc| assoc.func == func.sig.ident)
+            .find_map(|assoc| match assoc.assoc {
+                AssociationType::Keyed(key, _) => Some(key),
+                _ => None,
+            });
+        if let Some(key) = keyed {
+            return Err(Error::new_spanned(
+                key,
+                "Keyed associations are only supported for forward functions",
+            ));
+        }
+    }
+    if multi.is_some() && func.options.lookup.is_some() {
+        return Err(
+            func.error("`lookup` is not supported for functions returning several variants")
+        );
+    }
+    let infos = placeholder::variant_infos(variants);
     let mut arms = variants
         .iter()
-        .map(|variant| {
-            build_variant_arm(
-                variant,
-                &func.sig.ident,
-                associated_funcs.iter().map(|func| func.sig.ident.clone()),
-                is_option,
-                has_self,
-                &func.def,
-            )
+        .zip(&infos)
+        .map(|(variant, info)| {
+            build_variant_arm(variant, info, func, &associated_funcs, is_option, has_self)
         })
         .collect::<Result<Vec<(proc_macro2::TokenStream, Wildcard)>>>()?;
     if is_option
@@ -92,7 +237,7 @@ fn build_function(
             .iter()
             .any(|(_, wildcard)| matches!(wildcard, Wildcard::True))
     {
-        arms.push((quote!(_ => None,), Wildcard::True))
+        arms.push((quote!(_ => ::core::option::Option::None,), Wildcard::True))
     }
     // make sure wildcards are last
     if !has_self {
@@ -102,7 +247,7 @@ fn build_function(
     let match_on = if has_self {
         quote!(self)
     } else if func.sig.inputs.is_empty() {
-        return Err(syn::Error::new(func.span, "Missing parameter"));
+        return Err(func.error("Missing parameter"));
     } else {
         let mut result = quote!();
         for input in &func.sig.inputs {
@@ -122,11 +267,59 @@ fn build_function(
             }
         }
         if func.sig.inputs.len() > 1 {
+            if func.options.scrutinee.is_none()
+                && (func.options.lookup.is_some() || scrutinee::normalizes(&func.options))
+            {
+                return Err(func.error(
+                    "`lookup`, `trim` and `ascii_case_insensitive` require a function with a single parameter or a `match` expression",
+                ));
+            }
             result = quote!((#result));
         }
-        result
+        let bytes = scrutinee::has_byte_literals(variants, &func.sig.ident, &associated_funcs);
+        let result = scrutinee::build_scrutinee(&func.options, result, bytes);
+        if let Some(lookup) = func.options.lookup {
+            let body =
+                lookup::build_lookup(lookup, variants, func, &associated_funcs, is_option, result)?;
+            return Ok(quote! {
+                #vis #sig
+                {
+                    #body
+                }
+            });
+        }
+        if func.options.ascii_case_insensitive && !bytes {
+            quote!(::core::primitive::str::as_bytes(#result))
+        } else {
+            result
+        }
+    };
+    if let Some(multi) = multi {
+        let body = multi::build_multi(multi, variants, func, &associated_funcs, match_on)?;
+        return Ok(quote! {
+            #vis #sig
+            {
+                #body
+            }
+        });
+    }
+    let consts = if func.options.consts {
+        if !has_self {
+            return Err(func.error("`consts` is only supported for forward associations"));
+        }
+        variants
+            .iter()
+            .zip(&infos)
+            .map(|(variant, info)| {
+                build_variant_const(variant, info, func, &associated_funcs, is_option)
+            })
+            .collect::<Result<Vec<proc_macro2::TokenStream>>>()?
+    } else {
+        Vec::new()
     };
     Ok(quote! {
+        #(#consts)*
+
         #vis #sig
         {
             match #match_on
@@ -139,29 +332,41 @@ fn build_function(
 
 fn build_variant_arm(
     variant: &Variant,
-    func: &syn::Ident,
-    mut assoc_funcs: impl Iterator<Item = syn::Ident>,
+    info: &placeholder::VariantInfo,
+    func: &DeriveFunc,
+    assoc_funcs: &[syn::Ident],
     is_option: bool,
     has_self: bool,
-    def: &Option<proc_macro2::TokenStream>,
 ) -> Result<(proc_macro2::TokenStream, Wildcard)> {
-    // Partially parse associations
-    let assocs = Association::get_variant_assocs(variant, !has_self).filter(|assoc| {
-        assoc.func == *func || assoc_funcs.any(|assoc_func| assoc_func == assoc.func)
-    });
+    let assocs = variant_assocs(variant, &func.sig.ident, assoc_funcs, has_self);
     if has_self {
-        build_fwd_assoc(assocs, variant, is_option, func, def)
+        build_fwd_assoc(assocs, variant, info, is_option, func)
     } else {
-        build_rev_assoc(assocs, variant, is_option)
+        build_rev_assoc(assocs, variant, info, is_option, &func.options)
     }
 }
 
+/// The associations of a variant which apply to a function
+fn variant_assocs<'a>(
+    variant: &'a Variant,
+    func: &'a syn::Ident,
+    assoc_funcs: &'a [syn::Ident],
+    has_self: bool,
+) -> impl Iterator<Item = Association> + 'a {
+    // Partially parse associations
+    Association::get_variant_assocs(variant, !has_self).filter(move |assoc| {
+        matches!(assoc.assoc, AssociationType::DelegateAll)
+            || assoc.func == *func
+            || assoc_funcs.contains(&assoc.func)
+    })
+}
+
 fn build_fwd_assoc(
     assocs: impl Iterator<Item = Association>,
     variant: &Variant,
+    info: &placeholder::VariantInfo,
     is_option: bool,
-    func_ident: &syn::Ident,
-    def: &Option<proc_macro2::TokenStream>,
+    func: &DeriveFunc,
 ) -> Result<(proc_macro2::TokenStream, Wildcard)> {
     let var_ident = &variant.ident;
     let fields = match &variant.fields {
@@ -169,11 +374,10 @@ fn build_fwd_assoc(
             let named = fields
                 .named
                 .iter()
-                .map(|f| {
+                .enumerate()
+                .map(|(i, f)| {
                     let ident = &f.ident;
-                    let val: &Option<proc_macro2::Ident> = &f.ident.as_ref().map(|s| {
-                        proc_macro2::Ident::new(&("_".to_string() + &s.to_string()), f.span())
-                    });
+                    let val = field_binding(i, f);
                     quote!(#ident: #val)
                 })
                 .collect::<Vec<proc_macro2::TokenStream>>();
@@ -185,8 +389,7 @@ fn build_fwd_assoc(
                 .iter()
                 .enumerate()
                 .map(|(i, f)| {
-                    let ident =
-                        proc_macro2::Ident::new(&("_".to_string() + &i.to_string()), f.span());
+                    let ident = field_binding(i, f);
                     quote!(#ident)
                 })
                 .collect::<Vec<proc_macro2::TokenStream>>();
@@ -194,58 +397,419 @@ fn build_fwd_assoc(
         }
         _ => quote!(),
     };
-    let assocs = assocs
-        .filter_map(|assoc| {
-            if let AssociationType::Forward(expr) = assoc.assoc {
-                Some(Ok(expr))
-            } else {
-                None
+    let val = if func.options.consts {
+        let const_ident = variant_const_ident(variant, &func.sig.ident);
+        quote!(Self::#const_ident)
+    } else {
+        build_fwd_value(assocs, variant, info, is_option, func)?
+    };
+    // Spanned at the variant, so that errors in the arm point at it
+    Ok((
+        quote_spanned! { var_ident.span()=> Self::#var_ident #fields => #val, },
+        Wildcard::None,
+    ))
+}
+
+/// Builds the value a variant yields for a forward function, with placeholders
+/// replaced by the variant's metadata
+fn build_fwd_value(
+    assocs: impl Iterator<Item = Association>,
+    variant: &Variant,
+    info: &placeholder::VariantInfo,
+    is_option: bool,
+    func: &DeriveFunc,
+) -> Result<proc_macro2::TokenStream> {
+    let sig = &func.sig;
+    // Explicit associations take precedence over a variant-wide `delegate`
+    let mut delegate_all = false;
+    let mut vals = Vec::new();
+    let mut keyed = Vec::new();
+    let mut keyed_wildcard = false;
+    for assoc in assocs {
+        match assoc.assoc {
+            AssociationType::Forward(val) => {
+                vals.push(build_fwd_expr(&val, info, is_option, func)?)
             }
-        })
-        .collect::<Result<Vec<syn::Expr>>>()?;
-    match assocs.len() {
-        0 => {
-            if let Some(tokens) = def {
-                Ok(quote! { Self::#var_ident #fields => #tokens, })
-            } else if is_option {
-                Ok(quote! { Self::#var_ident #fields => None, })
-            } else {
-                Err(Error::new_spanned(
-                    variant,
-                    format!("Missing `assoc` attribute for {}", func_ident),
-                ))
+            AssociationType::Keyed(key, val) => {
+                let val = build_fwd_expr(&val, info, is_option, func)?;
+                keyed_wildcard |= matches!(key, syn::Pat::Wild(_));
+                keyed.push(quote_spanned!(key.span()=> #key => #val,));
             }
-        }
-        1 => {
-            let val = &assocs[0];
-            if is_option {
-                if quote!(#val).to_string().trim() == "None" {
-                    Ok(quote! { Self::#var_ident #fields => #val, })
+            AssociationType::Field(binding) => {
+                let span = binding.span();
+                let val = if derefs_field(sig) {
+                    quote_spanned!(span=> *#binding)
+                } else {
+                    quote!(#binding)
+                };
+                if is_option {
+                    vals.push(quote_spanned!(span=> ::core::option::Option::Some(#val)))
                 } else {
-                    Ok(quote! { Self::#var_ident #fields => Some(#val), })
+                    vals.push(val)
                 }
-            } else {
-                Ok(quote! { Self::#var_ident #fields => #val, })
             }
+            AssociationType::Delegate => vals.push(build_delegate_call(variant, sig)?),
+            AssociationType::DelegateAll => delegate_all = true,
+            AssociationType::Reverse(_) | AssociationType::Type(_) => (),
         }
+    }
+    if !keyed.is_empty() {
+        // Keys without a match fall back to the default, if there is one. The
+        // fallback is unreachable when the keys are exhaustive.
+        let fallback = if keyed_wildcard {
+            None
+        } else {
+            build_fwd_default(info, is_option, func)?.map(|val| {
+                quote! {
+                    #[allow(unreachable_patterns)]
+                    _ => #val,
+                }
+            })
+        };
+        let key = build_key(func)?;
+        vals.push(quote! {
+            match #key {
+                #(#keyed)*
+                #fallback
+            }
+        });
+    }
+    if vals.is_empty() && delegate_all {
+        vals.push(build_delegate_call(variant, sig)?);
+    }
+    match vals.len() {
+        0 => build_fwd_default(info, is_option, func)?.ok_or_else(|| {
+            Error::new_spanned(
+                variant,
+                format!("Missing `assoc` attribute for {}", sig.ident),
+            )
+        }),
+        1 => Ok(vals.remove(0)),
         _ => Err(Error::new_spanned(
             variant,
-            format!("Too many `assoc` attributes for {}", func_ident),
+            format!("Too many `assoc` attributes for {}", sig.ident),
         )),
     }
-    .map(|toks| (toks, Wildcard::None))
+}
+
+/// Builds the value of a forward association, wrapped in `Some` for functions
+/// returning an `Option` unless it is `None`
+pub(crate) fn build_fwd_expr(
+    val: &syn::Expr,
+    info: &placeholder::VariantInfo,
+    is_option: bool,
+    func: &DeriveFunc,
+) -> Result<proc_macro2::TokenStream> {
+    if is_option && quote!(#val).to_string().trim() == "None" {
+        return Ok(quote!(#val));
+    }
+    // Errors in the wrapping code point at the association
+    let span = val.span();
+    let val = placeholder::substitute(quote!(#val), info)?;
+    let val = if func.options.is_static {
+        build_static_value(func, &val)?
+    } else {
+        val
+    };
+    if is_option {
+        Ok(quote_spanned!(span=> ::core::option::Option::Some(#val)))
+    } else {
+        Ok(val)
+    }
+}
+
+/// The value of a forward function for a variant without an association: the
+/// function's default, or `None` for functions returning an `Option`
+pub(crate) fn build_fwd_default(
+    info: &placeholder::VariantInfo,
+    is_option: bool,
+    func: &DeriveFunc,
+) -> Result<Option<proc_macro2::TokenStream>> {
+    if let Some(tokens) = &func.def {
+        let tokens = placeholder::substitute(tokens.clone(), info)?;
+        if func.options.is_static {
+            build_static_value(func, &tokens).map(Some)
+        } else {
+            Ok(Some(tokens))
+        }
+    } else if is_option {
+        Ok(Some(quote!(::core::option::Option::None)))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Builds the expression keyed associations are matched against: the
+/// parameter of a forward function other than `self`, or a tuple of them
+fn build_key(func: &DeriveFunc) -> Result<proc_macro2::TokenStream> {
+    let params = func
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(pat_type) => {
+                let pat = &pat_type.pat;
+                (quote!(#pat).to_string().trim() != "self").then_some(pat)
+            }
+        })
+        .collect::<Vec<_>>();
+    match params.as_slice() {
+        [] => {
+            Err(func
+                .error("Keyed associations require a function with parameters other than `self`"))
+        }
+        [param] => Ok(quote!(#param)),
+        params => Ok(quote!((#(#params),*))),
+    }
+}
+
+/// Builds the associated constant holding a variant's value for a forward
+/// function with the `consts` option
+fn build_variant_const(
+    variant: &Variant,
+    info: &placeholder::VariantInfo,
+    func: &DeriveFunc,
+    assoc_funcs: &[syn::Ident],
+    is_option: bool,
+) -> Result<proc_macro2::TokenStream> {
+    let vis = &func.vis;
+    let ty = match &func.sig.output {
+        syn::ReturnType::Type(_, ty) => ty,
+        syn::ReturnType::Default => {
+            return Err(func.error("`consts` requires a function with a return type"));
+        }
+    };
+    if func.sig.inputs.len() > 1 {
+        return Err(func.error("`consts` requires a function without parameters other than `self`"));
+    }
+    let assocs = variant_assocs(variant, &func.sig.ident, assoc_funcs, true).collect::<Vec<_>>();
+    // A constant has no `self`, so it cannot bind the fields of the variant
+    let bindings = variant
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| field_binding(i, field))
+        .collect::<Vec<proc_macro2::Ident>>();
+    let has_value = assocs
+        .iter()
+        .any(|assoc| !matches!(assoc.assoc, AssociationType::DelegateAll));
+    let uses_fields = assocs.iter().any(|assoc| match &assoc.assoc {
+        AssociationType::Forward(val) | AssociationType::Keyed(_, val) => {
+            mentions_any(quote!(#val), &bindings)
+        }
+        AssociationType::Field(_) | AssociationType::Delegate => true,
+        AssociationType::DelegateAll => !has_value,
+        AssociationType::Reverse(_) | AssociationType::Type(_) => false,
+    });
+    if uses_fields {
+        return Err(Error::new_spanned(
+            &variant.ident,
+            format!(
+                "`consts` values cannot reference the fields of a variant, as the value of `{}` for `{}` does",
+                func.sig.ident, variant.ident
+            ),
+        ));
+    }
+    let val = build_fwd_value(assocs.into_iter(), variant, info, is_option, func)?;
+    let const_ident = variant_const_ident(variant, &func.sig.ident);
+    Ok(quote!(#vis const #const_ident: #ty = #val;))
+}
+
+/// Whether any of the given identifiers appears within some tokens
+fn mentions_any(tokens: proc_macro2::TokenStream, idents: &[proc_macro2::Ident]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => idents.contains(&ident),
+        proc_macro2::TokenTree::Group(group) => mentions_any(group.stream(), idents),
+        _ => false,
+    })
+}
+
+/// The name of the constant generated for a variant and function, eg.
+/// `READ_CODE` for the variant `Read` and the function `code`
+fn variant_const_ident(variant: &Variant, func: &syn::Ident) -> proc_macro2::Ident {
+    let name = format!(
+        "{}_{}",
+        to_snake_case(&variant.ident.unraw().to_string()),
+        to_snake_case(&func.unraw().to_string())
+    );
+    proc_macro2::Ident::new(&name.to_uppercase(), variant.ident.span())
+}
+
+/// Reports constants of functions with the `consts` option which would share a
+/// name, eg. `READ_WRITE_CODE` for both `ReadWrite` with `code` and `Read` with
+/// `write_code`
+fn check_const_collisions(variants: &[&Variant], fns: &[DeriveFuncs]) -> Result<()> {
+    let mut names: Vec<(proc_macro2::Ident, &Variant, &syn::Ident)> = Vec::new();
+    for func in fns
+        .iter()
+        .flat_map(|DeriveFuncs(funcs)| funcs)
+        .filter(|func| func.options.consts)
+    {
+        for variant in variants {
+            let const_ident = variant_const_ident(variant, &func.sig.ident);
+            if let Some((_, other, other_func)) =
+                names.iter().find(|(other, _, _)| *other == const_ident)
+            {
+                return Err(Error::new_spanned(
+                    &variant.ident,
+                    format!(
+                        "The constant `{}` for `{}` and {} collides with the one for `{}` and {}",
+                        const_ident, variant.ident, func.sig.ident, other.ident, other_func
+                    ),
+                ));
+            }
+            names.push((const_ident, variant, &func.sig.ident));
+        }
+    }
+    Ok(())
+}
+
+/// Converts an identifier from `CamelCase` (or `snake_case`) to `snake_case`.
+/// Acronyms are kept together, so `HTTPError` becomes `http_error`.
+fn to_snake_case(ident: &str) -> String {
+    let ident = ident.trim_start_matches("r#");
+    let chars = ident.chars().collect::<Vec<char>>();
+    let mut result = String::with_capacity(ident.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 && chars[i - 1] != '_' {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                result.push('_');
+            }
+        }
+        result.extend(c.to_lowercase());
+    }
+    result
+}
+
+/// Builds a call to the same function on the single field of a variant,
+/// forwarding all non-receiver arguments. The result is used as-is, so an
+/// `Option` returned by the inner function is not wrapped a second time.
+fn build_delegate_call(
+    variant: &Variant,
+    sig: &syn::Signature,
+) -> Result<proc_macro2::TokenStream> {
+    let field = match &variant.fields {
+        syn::Fields::Named(syn::FieldsNamed { named: fields, .. })
+        | syn::Fields::Unnamed(syn::FieldsUnnamed {
+            unnamed: fields, ..
+        }) if fields.len() == 1 => Some(field_binding(0, &fields[0])),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        Error::new_spanned(
+            variant,
+            "`delegate` requires a variant with exactly one field",
+        )
+    })?;
+    let args = sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(pat_type) => match &*pat_type.pat {
+                syn::Pat::Ident(pat_ident) if pat_ident.ident == "self" => None,
+                syn::Pat::Ident(pat_ident) => Some(Ok(&pat_ident.ident)),
+                pat => Some(Err(Error::new_spanned(
+                    pat,
+                    "`delegate` requires plain identifiers for function parameters",
+                ))),
+            },
+        })
+        .collect::<Result<Vec<&syn::Ident>>>()?;
+    // Spanned at the field, so that a missing method is reported there
+    let mut func_ident = sig.ident.clone();
+    func_ident.set_span(field.span());
+    Ok(quote!(#field.#func_ident(#(#args),*)))
+}
+
+/// The name a field is bound to within a forward association arm: `_name` for
+/// named fields and `_index` for unnamed ones.
+fn field_binding(index: usize, field: &syn::Field) -> proc_macro2::Ident {
+    match &field.ident {
+        Some(ident) => proc_macro2::Ident::new(&format!("_{}", ident), ident.span()),
+        None => proc_macro2::Ident::new(&format!("_{}", index), field.span()),
+    }
+}
+
+/// Whether a field bound by a forward function must be dereferenced to be
+/// returned, which is the case when `self` is borrowed but the function
+/// returns by value.
+fn derefs_field(sig: &syn::Signature) -> bool {
+    let borrows_self = match sig.inputs.first() {
+        Some(FnArg::Receiver(receiver)) => match &receiver.kind {
+            syn::ReceiverKind::Reference(..) => true,
+            syn::ReceiverKind::Typed(_, ty) => matches!(**ty, syn::Type::Reference(_)),
+            _ => false,
+        },
+        Some(FnArg::Typed(pat_type)) => matches!(*pat_type.ty, syn::Type::Reference(_)),
+        None => false,
+    };
+    let returns_ref = match &sig.output {
+        syn::ReturnType::Type(_, ty) => {
+            matches!(option_inner(ty).unwrap_or(ty), syn::Type::Reference(_))
+        }
+        syn::ReturnType::Default => false,
+    };
+    borrows_self && !returns_ref
+}
+
+/// If a type is an `Option<T>`, returns `T`
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .filter(|segment| segment.ident == "Option")
+            .and_then(|segment| match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) => args.args.first(),
+                _ => None,
+            })
+            .and_then(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            }),
+        _ => None,
+    }
+}
+
+/// Wraps a value in a lazily initialized static for functions with the
+/// `static` option, yielding a `&'static` reference to it
+fn build_static_value(func: &DeriveFunc, val: &impl ToTokens) -> Result<proc_macro2::TokenStream> {
+    let ty = match &func.sig.output {
+        syn::ReturnType::Type(_, ty) => match option_inner(ty).unwrap_or(ty) {
+            syn::Type::Reference(reference) => Some(&reference.elem),
+            _ => None,
+        },
+        syn::ReturnType::Default => None,
+    }
+    .ok_or_else(|| {
+        func.error("`static` requires a function returning `&'static T` or `Option<&'static T>`")
+    })?;
+    Ok(quote! {
+        {
+            static __ENUM_ASSOC_STATIC: ::std::sync::LazyLock<#ty> =
+                ::std::sync::LazyLock::new(|| #val);
+            &*__ENUM_ASSOC_STATIC
+        }
+    })
 }
 
 fn build_rev_assoc(
     assocs: impl Iterator<Item = Association>,
     variant: &Variant,
+    info: &placeholder::VariantInfo,
     is_option: bool,
+    options: &FuncOptions,
 ) -> Result<(proc_macro2::TokenStream, Wildcard)> {
     let var_ident = &variant.ident;
     let assocs = assocs
         .filter_map(|assoc| {
             if let AssociationType::Reverse(pat) = assoc.assoc {
-                Some(Ok(pat))
+                Some(placeholder::substitute_pat(pat, info))
             } else {
                 None
             }
@@ -261,10 +825,12 @@ fn build_rev_assoc(
                 "Reverse associations not allowed for tuple or struct-like variants",
             ));
         }
+        let arm_pat = scrutinee::normalize_pat(options, pat)?;
+        let span = pat.span();
         let arm = if is_option {
-            quote!(#pat => Some(Self::#var_ident),)
+            quote_spanned!(span=> #arm_pat => ::core::option::Option::Some(Self::#var_ident),)
         } else {
-            quote!(#pat => Self::#var_ident,)
+            quote_spanned!(span=> #arm_pat => Self::#var_ident,)
         };
         if matches!(pat, syn::Pat::Wild(_)) {
             if wildcard_pat.is_some() {
@@ -285,15 +851,32 @@ fn build_rev_assoc(
     Ok((quote!(#(#concrete_pats) *), wildcard_status))
 }
 
-/// A container for a function parsed within a `func` attribute. Note that the
-/// span of the `func` atribute is included because the syn nodes were
-/// manipulated as a string and have lost therr own span information.
+/// A container for a function parsed within a `func` attribute
 #[derive(Clone)]
 struct DeriveFunc {
     vis: syn::Visibility,
     sig: syn::Signature,
-    span: proc_macro2::Span,
     def: Option<proc_macro2::TokenStream>,
+    options: FuncOptions,
+}
+
+/// Options which may follow a function signature within a `func` attribute,
+/// eg. `#[func(pub fn code(&self) -> u8, consts)]`. Options apply to the
+/// function directly preceding them.
+#[derive(Clone, Default)]
+struct FuncOptions {
+    /// Emit an associated constant for each variant's value
+    consts: bool,
+    /// Store each variant's value in a lazily initialized static
+    is_static: bool,
+    /// Dispatch strategy for reverse functions over string literals
+    lookup: Option<lookup::Lookup>,
+    /// Expression matched on by a reverse function instead of its parameters
+    scrutinee: Option<syn::Expr>,
+    /// Trim whitespace from the input and string literal patterns
+    trim: bool,
+    /// Ignore ASCII case in the input and string literal patterns
+    ascii_case_insensitive: bool,
 }
 
 /// An association. Contains a function ident as well as the actual tokens of
@@ -306,6 +889,19 @@ struct Association {
 enum AssociationType {
     Forward(syn::Expr),
     Reverse(syn::Pat),
+    /// A forward value which only applies when the function's other
+    /// parameters match a pattern, eg. `label(Lang::Fr) = "Ouvrir"`
+    Keyed(syn::Pat, syn::Expr),
+    /// A type, for use with `assoc_type`
+    Type(syn::Type),
+    /// Return a field of the variant, identified by its binding
+    Field(proc_macro2::Ident),
+    /// Forward to the same function on the variant's only field
+    Delegate,
+    /// Like `Delegate`, but for every forward function without an explicit
+    /// association. The `func` of such an association is the `delegate`
+    /// keyword itself.
+    DelegateAll,
 }
 
 /// For reverse associations, this enum keeps track of wldcard patterns. For
@@ -319,27 +915,112 @@ enum Wildcard {
     True = 2,
 }
 
+impl DeriveFunc {
+    /// Whether this function takes `self`, making it a forward function
+    fn has_self(&self) -> bool {
+        match self.sig.inputs.first() {
+            Some(FnArg::Receiver(_)) => true,
+            Some(FnArg::Typed(pat_type)) => {
+                let pat = &pat_type.pat;
+                quote!(#pat).to_string().trim() == "self"
+            }
+            None => false,
+        }
+    }
+
+    /// An error pointing at the signature of this function
+    fn error(&self, message: impl std::fmt::Display) -> Error {
+        Error::new_spanned(&self.sig, message)
+    }
+}
+
 impl syn::parse::Parse for DeriveFunc {
     /// Parse a function signature from an attribute
     fn parse(input: syn::parse::ParseStream) -> Result<Self> {
         let vis = input.parse::<syn::Visibility>()?;
         let sig = input.parse::<syn::Signature>()?;
         let def = if let Ok(block) = input.parse::<syn::Block>() {
-            Some(proc_macro2::TokenStream::from(ToTokens::into_token_stream(
-                block,
-            )))
+            Some(ToTokens::into_token_stream(block))
         } else {
             None
         };
         Ok(DeriveFunc {
             vis,
             sig,
-            span: input.span(),
             def,
+            options: FuncOptions::default(),
         })
     }
 }
 
+/// A single function option, of form Ident or Ident = Expr
+struct FuncOption(syn::Ident, Option<syn::Expr>);
+impl syn::parse::Parse for FuncOption {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let ident = syn::Ident::parse_any(input)?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self(ident, value))
+    }
+}
+
+impl FuncOptions {
+    fn set(&mut self, option: FuncOption) -> Result<()> {
+        let FuncOption(ident, value) = option;
+        match (ident.to_string().as_str(), value) {
+            ("consts", None) => self.consts = true,
+            ("static", None) => self.is_static = true,
+            ("trim", None) => self.trim = true,
+            ("ascii_case_insensitive", None) => self.ascii_case_insensitive = true,
+            ("lookup", Some(value)) => self.lookup = Some(lookup::Lookup::parse(&value)?),
+            ("match", Some(value)) => self.scrutinee = Some(value),
+            ("lookup" | "match", None) => {
+                return Err(Error::new_spanned(
+                    &ident,
+                    format!("Function option `{}` requires a value", ident),
+                ));
+            }
+            (_, Some(value))
+                if ["consts", "static", "trim", "ascii_case_insensitive"]
+                    .iter()
+                    .any(|option| ident == option) =>
+            {
+                return Err(Error::new_spanned(
+                    value,
+                    format!("Function option `{}` does not take a value", ident),
+                ));
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    &ident,
+                    format!("Unknown function option `{}`", ident),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An entry within a `func` attribute: either a function or an option
+enum FuncItem {
+    Func(Box<DeriveFunc>),
+    Option(FuncOption),
+}
+impl syn::parse::Parse for FuncItem {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        // Function signatures always start with a keyword
+        if input.peek(syn::Ident) || input.peek(Token![static]) || input.peek(Token![match]) {
+            Ok(Self::Option(input.parse()?))
+        } else {
+            Ok(Self::Func(input.parse()?))
+        }
+    }
+}
+
 struct DeriveFuncs(Vec<DeriveFunc>);
 impl syn::parse::Parse for DeriveFuncs {
     /// Parse a list of function signatures form an attribute
@@ -353,56 +1034,225 @@ impl syn::parse::Parse for DeriveFuncs {
         })?;
         let content;
         parenthesized!(content in input);
-        Ok(Self(
-            content
-                .parse_terminated(DeriveFunc::parse, Token!(,))
-                .map(|parsed| parsed.into_iter().collect())?,
-        ))
+        Self::parse_list(&content)
     }
 }
 
-/// Used to parse forward associations, which are of form Ident = Expr
-struct ForwardAssocTokens(syn::Ident, syn::Expr);
+impl DeriveFuncs {
+    /// Parse the comma-separated functions and options within a `func`
+    /// attribute
+    fn parse_list(content: syn::parse::ParseStream) -> Result<Self> {
+        let mut funcs: Vec<DeriveFunc> = Vec::new();
+        for item in content.parse_terminated(<FuncItem as syn::parse::Parse>::parse, Token!(,))? {
+            match item {
+                FuncItem::Func(func) => funcs.push(*func),
+                FuncItem::Option(option) => funcs
+                    .last_mut()
+                    .ok_or_else(|| {
+                        Error::new_spanned(
+                            &option.0,
+                            "Function options must follow a function signature",
+                        )
+                    })?
+                    .options
+                    .set(option)?,
+            }
+        }
+        Ok(Self(funcs))
+    }
+}
+
+const DELEGATE_KEYWORD: &str = "delegate";
+
+/// Used to parse forward associations, which are of form Ident = Expr, or a
+/// delegation
+enum ForwardAssocTokens {
+    Value(syn::Ident, syn::Expr),
+    Keyed(syn::Ident, syn::Pat, syn::Expr),
+    Type(syn::Ident, syn::Type),
+    Delegate(DelegateTokens),
+}
 impl syn::parse::Parse for ForwardAssocTokens {
     fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        if DelegateTokens::peek(input) {
+            return Ok(Self::Delegate(input.parse()?));
+        }
         let ident = input.parse()?;
+        if input.peek(syn::token::Paren) {
+            let (key, expr) = parse_keyed(input)?;
+            return Ok(Self::Keyed(ident, key, expr));
+        }
         input.parse::<syn::Token!(=)>()?;
-        let expr = input.parse()?;
-        Ok(Self(ident, expr))
+        match parse_value_or_type(input, <syn::Expr as syn::parse::Parse>::parse)? {
+            Ok(expr) => Ok(Self::Value(ident, expr)),
+            Err(ty) => Ok(Self::Type(ident, ty)),
+        }
     }
 }
 
-/// Used to parse reverse associations, which are of form Ident = Pat
-struct ReverseAssocTokens(syn::Ident, syn::Pat);
+/// Used to parse reverse associations, which are of form Ident = Pat. Delegations
+/// and keyed associations are parsed as well so that they don't invalidate the
+/// rest of the attribute.
+enum ReverseAssocTokens {
+    Value(syn::Ident, syn::Pat),
+    Keyed(syn::Ident, syn::Pat, syn::Expr),
+    Type(syn::Ident, syn::Type),
+    Delegate(DelegateTokens),
+}
 impl syn::parse::Parse for ReverseAssocTokens {
     fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        if DelegateTokens::peek(input) {
+            return Ok(Self::Delegate(input.parse()?));
+        }
         let ident = input.parse()?;
+        if input.peek(syn::token::Paren) {
+            let (key, expr) = parse_keyed(input)?;
+            return Ok(Self::Keyed(ident, key, expr));
+        }
         input.parse::<syn::Token!(=)>()?;
-        let pat = syn::Pat::parse_multi_with_leading_vert(input)?;
-        Ok(Self(ident, pat))
+        match parse_value_or_type(input, syn::Pat::parse_multi_with_leading_vert)? {
+            Ok(pat) => Ok(Self::Value(ident, pat)),
+            Err(ty) => Ok(Self::Type(ident, ty)),
+        }
+    }
+}
+
+/// Parses the key and value of a keyed association, of form `(Pat) = Expr`
+/// following the function ident. Several patterns, as in `(Pat1, Pat2)`, form
+/// a tuple pattern matching functions with several parameters.
+fn parse_keyed(input: syn::parse::ParseStream) -> Result<(syn::Pat, syn::Expr)> {
+    let content;
+    let paren_token = parenthesized!(content in input);
+    let mut elems = Punctuated::<syn::Pat, Token![,]>::parse_terminated_with(&content, |input| {
+        syn::Pat::parse_multi_with_leading_vert(input)
+    })?;
+    let key = if elems.len() == 1 && !elems.trailing_punct() {
+        elems.pop()
+    } else {
+        None
+    }
+    .unwrap_or_else(|| {
+        syn::Pat::Tuple(syn::PatTuple {
+            attrs: Vec::new(),
+            paren_token,
+            elems,
+        })
+    });
+    input.parse::<syn::Token!(=)>()?;
+    Ok((key, input.parse()?))
+}
+
+/// Parses the value of an association with `parser`, falling back to parsing a
+/// type. Type associations (eg. `Payload = Vec<u8>`) are not always valid
+/// expressions or patterns, and would otherwise invalidate the whole attribute.
+fn parse_value_or_type<T>(
+    input: syn::parse::ParseStream,
+    parser: fn(syn::parse::ParseStream) -> Result<T>,
+) -> Result<std::result::Result<T, syn::Type>> {
+    let fork = input.fork();
+    let err = match parser(&fork) {
+        Ok(val) if fork.is_empty() || fork.peek(Token![,]) => {
+            input.advance_to(&fork);
+            return Ok(Ok(val));
+        }
+        Ok(_) => None,
+        Err(err) => Some(err),
+    };
+    input
+        .parse::<syn::Type>()
+        .map(Err)
+        .map_err(|type_err| err.unwrap_or(type_err))
+}
+
+/// Used to parse delegations, which are of form `delegate` (for all functions)
+/// or `delegate(func1, func2)`
+struct DelegateTokens(syn::Ident, Option<Punctuated<syn::Ident, Token![,]>>);
+impl DelegateTokens {
+    fn peek(input: syn::parse::ParseStream) -> bool {
+        let fork = input.fork();
+        fork.parse::<syn::Ident>()
+            .is_ok_and(|ident| ident == DELEGATE_KEYWORD && !fork.peek(Token![=]))
+    }
+}
+impl syn::parse::Parse for DelegateTokens {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let keyword = input.parse()?;
+        let funcs = if input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            Some(content.parse_terminated(syn::Ident::parse, Token![,])?)
+        } else {
+            None
+        };
+        Ok(Self(keyword, funcs))
+    }
+}
+
+impl From<DelegateTokens> for Vec<Association> {
+    fn from(val: DelegateTokens) -> Self {
+        match val.1 {
+            Some(funcs) => funcs
+                .into_iter()
+                .map(|func| Association {
+                    func,
+                    assoc: AssociationType::Delegate,
+                })
+                .collect(),
+            None => vec![Association {
+                func: val.0,
+                assoc: AssociationType::DelegateAll,
+            }],
+        }
     }
 }
 
-impl From<ForwardAssocTokens> for Association {
+impl From<ForwardAssocTokens> for Vec<Association> {
     fn from(val: ForwardAssocTokens) -> Self {
-        Association {
-            func: val.0,
-            assoc: AssociationType::Forward(val.1),
+        match val {
+            ForwardAssocTokens::Value(func, expr) => vec![Association {
+                func,
+                assoc: AssociationType::Forward(expr),
+            }],
+            ForwardAssocTokens::Keyed(func, key, expr) => vec![Association {
+                func,
+                assoc: AssociationType::Keyed(key, expr),
+            }],
+            ForwardAssocTokens::Type(func, ty) => vec![Association {
+                func,
+                assoc: AssociationType::Type(ty),
+            }],
+            ForwardAssocTokens::Delegate(delegate) => delegate.into(),
         }
     }
 }
 
-impl From<ReverseAssocTokens> for Association {
+impl From<ReverseAssocTokens> for Vec<Association> {
     fn from(val: ReverseAssocTokens) -> Self {
-        Association {
-            func: val.0,
-            assoc: AssociationType::Reverse(val.1),
+        match val {
+            ReverseAssocTokens::Value(func, pat) => vec![Association {
+                func,
+                assoc: AssociationType::Reverse(pat),
+            }],
+            ReverseAssocTokens::Keyed(func, key, expr) => vec![Association {
+                func,
+                assoc: AssociationType::Keyed(key, expr),
+            }],
+            ReverseAssocTokens::Type(func, ty) => vec![Association {
+                func,
+                assoc: AssociationType::Type(ty),
+            }],
+            ReverseAssocTokens::Delegate(delegate) => delegate.into(),
         }
     }
 }
 
 impl Association {
     fn get_variant_assocs(variant: &Variant, is_reverse: bool) -> impl Iterator<Item = Self> + '_ {
+        let field_assocs = if is_reverse {
+            Vec::new()
+        } else {
+            Self::get_field_assocs(variant).collect()
+        };
         variant
             .attrs
             .iter()
@@ -416,7 +1266,7 @@ impl Association {
                             .map(|tokens| {
                                 tokens
                                     .into_iter()
-                                    .map(|tokens| tokens.into())
+                                    .flat_map(Vec::<Self>::from)
                                     .collect::<Vec<Self>>()
                             })
                             .ok()
@@ -427,7 +1277,7 @@ impl Association {
                             .map(|tokens| {
                                 tokens
                                     .into_iter()
-                                    .map(|tokens| tokens.into())
+                                    .flat_map(Vec::<Self>::from)
                                     .collect::<Vec<Self>>()
                             })
                             .ok()
@@ -437,5 +1287,176 @@ impl Association {
                 }
             })
             .flatten()
+            .chain(field_assocs)
+    }
+
+    /// Field associations are of form `#[assoc(func1, func2)]` on a field,
+    /// and make the field the value of those functions.
+    fn get_field_assocs(variant: &Variant) -> impl Iterator<Item = Self> + '_ {
+        variant.fields.iter().enumerate().flat_map(|(i, field)| {
+            field
+                .attrs
+                .iter()
+                .filter(|assoc_attr| assoc_attr.path().is_ident(ASSOC_ATTR))
+                .filter_map(move |attr| {
+                    if let syn::Meta::List(meta_list) = &attr.meta {
+                        let parser = Punctuated::<syn::Ident, Token![,]>::parse_terminated;
+                        parser
+                            .parse2(meta_list.tokens.clone())
+                            .map(|funcs| {
+                                funcs
+                                    .into_iter()
+                                    .map(|func| Association {
+                                        func,
+                                        assoc: AssociationType::Field(field_binding(i, field)),
+                                    })
+                                    .collect::<Vec<Self>>()
+                            })
+                            .ok()
+                    } else {
+                        None
+                    }
+                })
+                .flatten()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Derives `Assoc` for an enum, returning the message of the error it
+    /// fails with
+    fn derive_error(ast: syn::DeriveInput) -> String {
+        impl_macro(&ast)
+            .expect_err("Expected the derive to fail")
+            .to_string()
+    }
+
+    #[test]
+    fn test_debug_render() {
+        let ast = syn::parse_quote! {
+            #[func(pub fn debug_code(&self) -> u8 { 0 })]
+            enum DebugEnum {
+                #[assoc(debug_code = 1)]
+                First,
+                Second,
+            }
+        };
+        let text = debug::render(&ast, &impl_macro(&ast).unwrap());
+        assert_eq!(
+            text,
+            "// Expansion of #[derive(Assoc)] for DebugEnum
+
+#[allow(clippy::used_underscore_binding)]
+impl DebugEnum {
+    pub fn debug_code(&self) -> u8 {
+        match self {
+            Self::First => 1,
+            Self::Second => 0,
+        }
+    }
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_const_collisions() {
+        let message = derive_error(syn::parse_quote! {
+            #[func(pub const fn code(&self) -> u8, consts)]
+            #[func(pub const fn write_code(&self) -> u8, consts)]
+            enum Access {
+                #[assoc(code = 1, write_code = 2)]
+                ReadWrite,
+                #[assoc(code = 3, write_code = 4)]
+                Read,
+            }
+        });
+        assert_eq!(
+            message,
+            "The constant `READ_WRITE_CODE` for `Read` and write_code collides with the one for `ReadWrite` and code"
+        );
+    }
+
+    #[test]
+    fn test_const_fields() {
+        for variant in [
+            quote!(
+                #[assoc(code = _0 + 1)]
+                Read(u8)
+            ),
+            quote!(
+                #[assoc(code = _level)]
+                Read { level: u8 }
+            ),
+            quote!(
+                #[assoc(delegate)]
+                Read(Inner)
+            ),
+            quote!(Read(
+                #[assoc(code)]
+                u8
+            )),
+        ] {
+            let message = derive_error(syn::parse_quote! {
+                #[func(pub const fn code(&self) -> u8, consts)]
+                enum Access {
+                    #variant,
+                }
+            });
+            assert_eq!(
+                message,
+                "`consts` values cannot reference the fields of a variant, as the value of `code` for `Read` does",
+                "{}",
+                variant
+            );
+        }
+    }
+
+    #[test]
+    fn test_keyed_reverse() {
+        let message = derive_error(syn::parse_quote! {
+            #[func(pub fn from_code(code: u8) -> Option<Self>)]
+            enum Access {
+                #[assoc(from_code(1) = 2)]
+                Read,
+            }
+        });
+        assert_eq!(
+            message,
+            "Keyed associations are only supported for forward functions"
+        );
+    }
+
+    #[test]
+    fn test_slice_float_key() {
+        let message = derive_error(syn::parse_quote! {
+            #[func(pub fn with_ratio(ratio: f32) -> &'static [Self])]
+            enum Access {
+                #[assoc(with_ratio = 1.0)]
+                Read,
+            }
+        });
+        assert_eq!(
+            message,
+            "Functions returning `&'static [Self]` cannot compare this literal with other keys, consider `Vec<Self>` or `impl Iterator<Item = Self>`"
+        );
+    }
+
+    #[test]
+    fn test_unknown_collection() {
+        let message = derive_error(syn::parse_quote! {
+            #[func(pub fn from_code(code: u8) -> Rc<Self>)]
+            enum Access {
+                #[assoc(from_code = 1)]
+                Read,
+            }
+        });
+        assert_eq!(
+            message,
+            "`Rc` is not a supported collection, expected one of `Vec`, `VecDeque`, `LinkedList`, `BinaryHeap`, `BTreeSet`, `HashSet`"
+        );
     }
 }
@@ -0,0 +1,336 @@
+//! Alternative dispatch strategies for reverse functions whose associations
+//! are string or byte string literals, selected with the `lookup` function
+//! option. By default, these functions are a single `match` which compares the
+//! input against each literal in turn. Both strategies here keep the semantics
+//! of that `match`: the first matching association wins, and a wildcard is
+//! only used when nothing else matches.
+
+use quote::quote;
+use syn::{Error, Result, Variant};
+
+use crate::{
+    AssociationType, DeriveFunc, placeholder,
+    scrutinee::{build_bytes_pat, normalize_literal, pat_cases},
+    variant_assocs,
+};
+
+const HASH_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const MIX_MULTIPLIER: u64 = 0xff51_afd7_ed55_8ccd;
+/// Average number of keys per bucket of the perfect hash function
+const PHF_LAMBDA: usize = 5;
+/// Number of seeds to try before giving up on a perfect hash function
+const PHF_MAX_SEEDS: u64 = 64;
+
+#[derive(Clone, Copy)]
+pub(crate) enum Lookup {
+    /// Match on the length of the input, then on each byte
+    Trie,
+    /// Look the input up in a perfect hash table built during expansion
+    Phf,
+}
+
+impl Lookup {
+    pub(crate) fn parse(value: &syn::Expr) -> Result<Self> {
+        match value {
+            syn::Expr::Path(path) if path.path.is_ident("trie") => Ok(Self::Trie),
+            syn::Expr::Path(path) if path.path.is_ident("phf") => Ok(Self::Phf),
+            _ => Err(Error::new_spanned(
+                value,
+                "Expected `trie` or `phf` as the lookup strategy",
+            )),
+        }
+    }
+}
+
+/// The literal keys of a reverse function, in match order
+struct Keys {
+    /// Each distinct key and the index of the variant it maps to
+    keys: Vec<(Vec<u8>, usize)>,
+    /// The index of the variant with a wildcard association, if any
+    wildcard: Option<usize>,
+    /// Whether the keys are byte strings rather than strings
+    bytes: bool,
+}
+
+/// Builds the body of a reverse function using a lookup strategy, where
+/// `scrutinee` is the (possibly trimmed) input
+pub(crate) fn build_lookup(
+    lookup: Lookup,
+    variants: &[&Variant],
+    func: &DeriveFunc,
+    assoc_funcs: &[syn::Ident],
+    is_option: bool,
+    scrutinee: proc_macro2::TokenStream,
+) -> Result<proc_macro2::TokenStream> {
+    let Keys {
+        keys,
+        wildcard,
+        bytes,
+    } = collect_keys(variants, func, assoc_funcs)?;
+    let value = |index: usize| {
+        let var_ident = &variants[index].ident;
+        if is_option {
+            quote!(::core::option::Option::Some(Self::#var_ident))
+        } else {
+            quote!(Self::#var_ident)
+        }
+    };
+    let fallback = match wildcard {
+        Some(index) => value(index),
+        None if is_option => quote!(::core::option::Option::None),
+        None => {
+            return Err(func.error(
+                "`lookup` requires a function returning `Option<Self>` or a wildcard association",
+            ));
+        }
+    };
+    if keys.is_empty() {
+        return Ok(fallback);
+    }
+    let key = if bytes {
+        scrutinee
+    } else {
+        quote!(::core::primitive::str::as_bytes(#scrutinee))
+    };
+    // Input bytes are lowercased when hashing and comparing, as the keys are
+    let fold = if func.options.ascii_case_insensitive {
+        quote!(.to_ascii_lowercase())
+    } else {
+        quote!()
+    };
+    match lookup {
+        Lookup::Trie => {
+            let arms = keys.iter().map(|(key, index)| {
+                let pat = build_bytes_pat(&func.options, key);
+                let value = value(*index);
+                quote!(#pat => #value,)
+            });
+            Ok(quote! {
+                let key: &[u8] = #key;
+                match key {
+                    #(#arms)*
+                    _ => #fallback,
+                }
+            })
+        }
+        Lookup::Phf => {
+            let phf = Phf::generate(&keys)
+                .ok_or_else(|| func.error("Failed to generate a perfect hash function"))?;
+            let seed = HASH_OFFSET ^ phf.seed;
+            let buckets = phf.disps.len() as u32;
+            let len = phf.slots.len() as u32;
+            let disps = phf.disps.iter().map(|(d1, d2)| quote!((#d1, #d2)));
+            let slots = phf.slots.iter().map(|key| match key {
+                Some(key) => {
+                    let (bytes, index) = &keys[*key];
+                    let bytes = proc_macro2::Literal::byte_string(bytes);
+                    quote!((#bytes, #index))
+                }
+                None => quote!((b"", usize::MAX)),
+            });
+            let arms = (0..variants.len())
+                .filter(|index| keys.iter().any(|(_, key_index)| key_index == index))
+                .map(|index| {
+                    let value = value(index);
+                    quote!(#index => return #value,)
+                });
+            Ok(quote! {
+                const DISPS: &[(u32, u32)] = &[#(#disps),*];
+                const SLOTS: &[(&[u8], usize)] = &[#(#slots),*];
+                let key: &[u8] = #key;
+                let mut hash: u64 = #seed ^ key.len() as u64;
+                let mut i = 0;
+                while i + 8 <= key.len() {
+                    let chunk = u64::from_le_bytes([
+                        key[i]#fold, key[i + 1]#fold, key[i + 2]#fold, key[i + 3]#fold,
+                        key[i + 4]#fold, key[i + 5]#fold, key[i + 6]#fold, key[i + 7]#fold,
+                    ]);
+                    hash = (hash ^ chunk).wrapping_mul(#MIX_MULTIPLIER).rotate_left(29);
+                    i += 8;
+                }
+                if i < key.len() {
+                    let mut chunk = 0u64;
+                    let mut j = 0;
+                    while i + j < key.len() {
+                        chunk |= (key[i + j]#fold as u64) << (j * 8);
+                        j += 1;
+                    }
+                    hash = (hash ^ chunk).wrapping_mul(#MIX_MULTIPLIER).rotate_left(29);
+                }
+                hash ^= hash >> 33;
+                hash = hash.wrapping_mul(#MIX_MULTIPLIER);
+                hash ^= hash >> 33;
+                let (d1, d2) = DISPS[(hash >> 32) as usize & (#buckets as usize - 1)];
+                let slot = (hash as u32)
+                    .wrapping_add(d1.wrapping_mul((hash >> 24) as u32))
+                    .wrapping_add(d2)
+                    & (#len - 1);
+                let (slot_key, variant) = SLOTS[slot as usize];
+                if slot_key.len() == key.len() {
+                    let mut i = 0;
+                    while i < key.len() && slot_key[i] == key[i]#fold {
+                        i += 1;
+                    }
+                    if i == key.len() {
+                        match variant {
+                            #(#arms)*
+                            _ => (),
+                        }
+                    }
+                }
+                #fallback
+            })
+        }
+    }
+}
+
+/// Collects the keys of a reverse function in the order a `match` would try
+/// them, skipping any which are already matched by an earlier association
+fn collect_keys(
+    variants: &[&Variant],
+    func: &DeriveFunc,
+    assoc_funcs: &[syn::Ident],
+) -> Result<Keys> {
+    let mut keys: Vec<(Vec<u8>, usize)> = Vec::new();
+    let mut wildcard = None;
+    let mut bytes = None;
+    let infos = placeholder::variant_infos(variants);
+    for (index, (variant, info)) in variants.iter().zip(&infos).enumerate() {
+        let pats =
+            variant_assocs(variant, &func.sig.ident, assoc_funcs, false).filter_map(|assoc| {
+                match assoc.assoc {
+                    AssociationType::Reverse(pat) => Some(pat),
+                    _ => None,
+                }
+            });
+        for pat in pats {
+            let pat = placeholder::substitute_pat(pat, info)?;
+            if !matches!(variant.fields, syn::Fields::Unit) {
+                return Err(Error::new_spanned(
+                    variant,
+                    "Reverse associations not allowed for tuple or struct-like variants",
+                ));
+            }
+            for case in pat_cases(&pat) {
+                let (key, is_bytes) = match case {
+                    syn::Pat::Wild(_) if wildcard.is_some() => {
+                        return Err(Error::new_spanned(
+                            case,
+                            "Only 1 wildcard allowed per reverse association",
+                        ));
+                    }
+                    syn::Pat::Wild(_) => {
+                        wildcard = Some(index);
+                        continue;
+                    }
+                    syn::Pat::Lit(syn::PatLit {
+                        lit: lit @ (syn::Lit::Str(_) | syn::Lit::ByteStr(_)),
+                        ..
+                    }) => (
+                        normalize_literal(&func.options, lit).unwrap_or_default(),
+                        matches!(lit, syn::Lit::ByteStr(_)),
+                    ),
+                    _ => {
+                        return Err(Error::new_spanned(
+                            case,
+                            "`lookup` requires string or byte string literal patterns",
+                        ));
+                    }
+                };
+                if *bytes.get_or_insert(is_bytes) != is_bytes {
+                    return Err(Error::new_spanned(
+                        case,
+                        "`lookup` cannot mix string and byte string patterns",
+                    ));
+                }
+                if !keys.iter().any(|(existing, _)| *existing == key) {
+                    keys.push((key, index));
+                }
+            }
+        }
+    }
+    Ok(Keys {
+        keys,
+        wildcard,
+        bytes: bytes.unwrap_or(false),
+    })
+}
+
+/// A perfect hash function using the "hash, displace and compress" scheme.
+/// Keys are hashed into buckets, and each bucket is given a displacement which
+/// places all of its keys into free slots of the table.
+struct Phf {
+    seed: u64,
+    disps: Vec<(u32, u32)>,
+    /// The index of the key in each slot of the table, if any
+    slots: Vec<Option<usize>>,
+}
+
+impl Phf {
+    fn generate(keys: &[(Vec<u8>, usize)]) -> Option<Self> {
+        (0..PHF_MAX_SEEDS).find_map(|seed| Self::try_generate(seed, keys))
+    }
+
+    fn try_generate(seed: u64, keys: &[(Vec<u8>, usize)]) -> Option<Self> {
+        let len = keys.len().next_power_of_two();
+        let buckets_len = keys.len().div_ceil(PHF_LAMBDA).next_power_of_two();
+        let hashes = keys
+            .iter()
+            .map(|(key, _)| hash(seed, key))
+            .collect::<Vec<(u32, u32, u32)>>();
+        let mut buckets = vec![Vec::new(); buckets_len];
+        for (key, (bucket, _, _)) in hashes.iter().enumerate() {
+            buckets[*bucket as usize & (buckets_len - 1)].push(key);
+        }
+        let mut order = (0..buckets_len).collect::<Vec<usize>>();
+        order.sort_by_key(|bucket| std::cmp::Reverse(buckets[*bucket].len()));
+        let mut slots: Vec<Option<usize>> = vec![None; len];
+        let mut disps = vec![(0, 0); buckets_len];
+        for bucket in order {
+            let (d1, d2, placed) = (0..len as u32)
+                .flat_map(|d1| (0..len as u32).map(move |d2| (d1, d2)))
+                .find_map(|(d1, d2)| {
+                    let mut placed: Vec<usize> = Vec::with_capacity(buckets[bucket].len());
+                    for key in &buckets[bucket] {
+                        let (_, f1, f2) = hashes[*key];
+                        let slot = (f1.wrapping_add(d1.wrapping_mul(f2)).wrapping_add(d2)
+                            & (len as u32 - 1)) as usize;
+                        if slots[slot].is_some() || placed.contains(&slot) {
+                            return None;
+                        }
+                        placed.push(slot);
+                    }
+                    Some((d1, d2, placed))
+                })?;
+            for (key, slot) in buckets[bucket].iter().zip(placed) {
+                slots[slot] = Some(*key);
+            }
+            disps[bucket] = (d1, d2);
+        }
+        Some(Self { seed, disps, slots })
+    }
+}
+
+/// The hash used by the perfect hash function, which must produce the same
+/// results as the generated code. The key is consumed in little endian chunks
+/// of 8 bytes, and the result goes through a finalizer before being split into
+/// a bucket hash and two displacement hashes.
+fn hash(seed: u64, key: &[u8]) -> (u32, u32, u32) {
+    let mut hash = HASH_OFFSET ^ seed ^ key.len() as u64;
+    for chunk in key.chunks(8) {
+        let chunk = chunk
+            .iter()
+            .enumerate()
+            .fold(0u64, |acc, (j, byte)| acc | (*byte as u64) << (j * 8));
+        hash = (hash ^ chunk).wrapping_mul(MIX_MULTIPLIER).rotate_left(29);
+    }
+    let hash = mix(hash);
+    ((hash >> 32) as u32, hash as u32, (hash >> 24) as u32)
+}
+
+fn mix(mut hash: u64) -> u64 {
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(MIX_MULTIPLIER);
+    hash ^= hash >> 33;
+    hash
+}
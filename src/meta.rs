@@ -0,0 +1,201 @@
+//! Runtime metadata of associations, enabled with `#[assoc_meta]` on the
+//! enum. Each association gathered for a function becomes an entry in a static
+//! table, which can be listed or searched by function name at runtime.
+
+use quote::{format_ident, quote};
+use syn::{Error, Result, Variant, ext::IdentExt};
+
+use crate::{
+    ASSOC_META_ATTR, AssociationType, DeriveFunc, DeriveFuncs, to_snake_case, variant_assocs,
+};
+
+/// Builds the metadata table and lookup function, which go in the `impl` block
+/// of the enum, along with the module defining the metadata type
+pub(crate) fn build_meta(
+    ast: &syn::DeriveInput,
+    variants: &[&Variant],
+    fns: &[DeriveFuncs],
+) -> Result<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    let Some(attr) = ast
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(ASSOC_META_ATTR))
+    else {
+        return Ok((quote!(), quote!()));
+    };
+    if !ast.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            attr,
+            "`assoc_meta` is not supported for generic enums",
+        ));
+    }
+    let vis = &ast.vis;
+    let name = &ast.ident;
+    let mod_ident = format_ident!("{}_meta", to_snake_case(&name.to_string()));
+    let mod_doc = format!("Association metadata of [`{0}`](super::{0})", name);
+    let mut entries = Vec::new();
+    for variant in variants {
+        for DeriveFuncs(funcs) in fns {
+            let assoc_funcs = funcs
+                .iter()
+                .map(|func| func.sig.ident.clone())
+                .collect::<Vec<syn::Ident>>();
+            for func in funcs {
+                entries.extend(build_entries(name, &mod_ident, variant, func, &assoc_funcs));
+            }
+        }
+    }
+    let name_arms = variants.iter().map(|variant| {
+        let var_ident = &variant.ident;
+        let var_name = var_ident.unraw().to_string();
+        quote!(Self::#var_ident { .. } => #var_name,)
+    });
+    let in_impl = quote! {
+        /// Metadata of every association, in the order of the variants
+        #vis const ASSOC_META: &'static [#mod_ident::AssocMeta<Self>] = &[#(#entries),*];
+
+        /// Returns the metadata of this variant's association for the function
+        /// with the given name, if any
+        #vis fn assoc_meta(
+            &self,
+            func: &::core::primitive::str,
+        ) -> ::core::option::Option<&'static #mod_ident::AssocMeta<Self>> {
+            let variant = match self {
+                #(#name_arms)*
+            };
+            Self::ASSOC_META
+                .iter()
+                .find(|meta| meta.variant == variant && meta.func == func)
+        }
+    };
+    let module = quote! {
+        #[doc = #mod_doc]
+        #[allow(dead_code)]
+        #vis mod #mod_ident {
+            /// An association of a variant of `E` with a function
+            #[derive(::core::fmt::Debug)]
+            pub struct AssocMeta<E: 'static> {
+                /// The name of the variant
+                pub variant: &'static ::core::primitive::str,
+                /// The name of the function
+                pub func: &'static ::core::primitive::str,
+                /// The tokens of the associated value (or pattern, for reverse
+                /// functions) as written, or `key => value` for keyed ones
+                pub tokens: &'static ::core::primitive::str,
+                /// Writes the value of the function for a variant with
+                /// `Debug`, returning the result of writing. This is only
+                /// available for forward functions which take `&self` alone
+                /// and return a `Debug` type, and returns `None` otherwise.
+                pub format: fn(
+                    &E,
+                    &mut dyn ::core::fmt::Write,
+                ) -> ::core::option::Option<::core::fmt::Result>,
+            }
+
+            impl<E> ::core::clone::Clone for AssocMeta<E> {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+            impl<E> ::core::marker::Copy for AssocMeta<E> {}
+
+            #[doc(hidden)]
+            pub struct Wrap<T>(pub T);
+
+            // Dispatches to `FormatDebug` when the value implements `Debug`,
+            // and `FormatNone` otherwise, as the latter needs an extra
+            // reference
+            #[doc(hidden)]
+            pub trait FormatDebug {
+                fn format(
+                    &self,
+                    out: &mut dyn ::core::fmt::Write,
+                ) -> ::core::option::Option<::core::fmt::Result>;
+            }
+            impl<T: ::core::fmt::Debug> FormatDebug for Wrap<T> {
+                fn format(
+                    &self,
+                    out: &mut dyn ::core::fmt::Write,
+                ) -> ::core::option::Option<::core::fmt::Result> {
+                    ::core::option::Option::Some(::core::write!(out, "{:?}", self.0))
+                }
+            }
+
+            #[doc(hidden)]
+            pub trait FormatNone {
+                fn format(
+                    &self,
+                    out: &mut dyn ::core::fmt::Write,
+                ) -> ::core::option::Option<::core::fmt::Result>;
+            }
+            impl<T> FormatNone for &Wrap<T> {
+                fn format(
+                    &self,
+                    _: &mut dyn ::core::fmt::Write,
+                ) -> ::core::option::Option<::core::fmt::Result> {
+                    ::core::option::Option::None
+                }
+            }
+        }
+    };
+    Ok((in_impl, module))
+}
+
+/// Builds the metadata entries of a variant's associations for a function
+fn build_entries(
+    name: &syn::Ident,
+    mod_ident: &syn::Ident,
+    variant: &Variant,
+    func: &DeriveFunc,
+    assoc_funcs: &[syn::Ident],
+) -> Vec<proc_macro2::TokenStream> {
+    let has_self = func.has_self();
+    let func_ident = &func.sig.ident;
+    let func_name = func_ident.unraw().to_string();
+    let var_name = variant.ident.unraw().to_string();
+    let format = match func.sig.inputs.first() {
+        Some(syn::FnArg::Receiver(receiver))
+            if func.sig.inputs.len() == 1
+                && matches!(receiver.kind, syn::ReceiverKind::Reference(..)) =>
+        {
+            quote! {
+                |value: &#name, out: &mut dyn ::core::fmt::Write| {
+                    use #mod_ident::{FormatDebug as _, FormatNone as _};
+                    (&#mod_ident::Wrap(value.#func_ident())).format(out)
+                }
+            }
+        }
+        _ => quote!(|_: &#name, _: &mut dyn ::core::fmt::Write| ::core::option::Option::None),
+    };
+    let assocs = variant_assocs(variant, func_ident, assoc_funcs, has_self).collect::<Vec<_>>();
+    // A variant-wide `delegate` only applies without an explicit association
+    let explicit = assocs
+        .iter()
+        .any(|assoc| !matches!(assoc.assoc, AssociationType::DelegateAll));
+    assocs
+        .into_iter()
+        .filter_map(|assoc| match assoc.assoc {
+            AssociationType::Forward(expr) if has_self => Some(quote!(#expr).to_string()),
+            AssociationType::Keyed(key, expr) if has_self => {
+                Some(format!("{} => {}", quote!(#key), quote!(#expr)))
+            }
+            AssociationType::Field(binding) if has_self => Some(binding.to_string()),
+            AssociationType::Delegate if has_self => Some(crate::DELEGATE_KEYWORD.to_string()),
+            AssociationType::DelegateAll if has_self && !explicit => {
+                Some(crate::DELEGATE_KEYWORD.to_string())
+            }
+            AssociationType::Reverse(pat) if !has_self => Some(quote!(#pat).to_string()),
+            _ => None,
+        })
+        .map(|tokens| {
+            quote! {
+                #mod_ident::AssocMeta {
+                    variant: #var_name,
+                    func: #func_name,
+                    tokens: #tokens,
+                    format: #format,
+                }
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,239 @@
+//! Reverse functions which return every matching variant rather than the first,
+//! selected by their return type: `&'static [Self]`, `impl Iterator<Item = Self>`
+//! or one of the standard collections of `Self`, such as `Vec<Self>`.
+
+use quote::quote;
+use syn::{Error, Result, Variant};
+
+use crate::{AssociationType, DeriveFunc, FuncOptions, placeholder, scrutinee, variant_assocs};
+
+/// The collections which may be returned by a reverse function, built from an
+/// iterator over the matching variants
+const COLLECTIONS: [&str; 6] = [
+    "Vec",
+    "VecDeque",
+    "LinkedList",
+    "BinaryHeap",
+    "BTreeSet",
+    "HashSet",
+];
+
+/// The kind of value a reverse function returning several variants builds
+pub(crate) enum Multi {
+    /// A static slice, with one per distinct pattern
+    Slice,
+    /// An iterator over the matching variants
+    Iter,
+    /// A collection built from the iterator, such as `Vec<Self>`
+    Collect,
+}
+
+impl Multi {
+    /// The kind of a reverse function's return type, if it returns several
+    /// variants. Any other generic type of `Self` than `Option` and the known
+    /// collections is an error.
+    pub(crate) fn of(sig: &syn::Signature) -> Result<Option<Self>> {
+        let syn::ReturnType::Type(_, ty) = &sig.output else {
+            return Ok(None);
+        };
+        Ok(match &**ty {
+            syn::Type::Reference(reference) => match &*reference.elem {
+                syn::Type::Slice(slice) if is_self(&slice.elem) => Some(Self::Slice),
+                _ => None,
+            },
+            syn::Type::ImplTrait(impl_trait) => impl_trait
+                .bounds
+                .iter()
+                .any(|bound| match bound {
+                    syn::TypeParamBound::Trait(bound) => bound
+                        .path
+                        .segments
+                        .last()
+                        .is_some_and(|segment| segment.ident == "Iterator"),
+                    _ => false,
+                })
+                .then_some(Self::Iter),
+            syn::Type::Path(path) => {
+                let Some(segment) = path.path.segments.last() else {
+                    return Ok(None);
+                };
+                let of_self = match &segment.arguments {
+                    syn::PathArguments::AngleBracketed(args) => {
+                        matches!(
+                            args.args.first(),
+                            Some(syn::GenericArgument::Type(ty)) if is_self(ty)
+                        ) && args.args.len() == 1
+                    }
+                    _ => false,
+                };
+                if !of_self || segment.ident == "Option" {
+                    None
+                } else if COLLECTIONS.iter().any(|name| segment.ident == name) {
+                    Some(Self::Collect)
+                } else {
+                    return Err(Error::new_spanned(
+                        ty,
+                        format!(
+                            "`{}` is not a supported collection, expected one of {}",
+                            segment.ident,
+                            COLLECTIONS.map(|name| format!("`{}`", name)).join(", ")
+                        ),
+                    ));
+                }
+            }
+            _ => None,
+        })
+    }
+}
+
+fn is_self(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(path) if path.qself.is_none() && path.path.is_ident("Self"))
+}
+
+/// Builds the body of a reverse function returning every variant whose
+/// patterns match the scrutinee
+pub(crate) fn build_multi(
+    multi: Multi,
+    variants: &[&Variant],
+    func: &DeriveFunc,
+    assoc_funcs: &[syn::Ident],
+    scrutinee: proc_macro2::TokenStream,
+) -> Result<proc_macro2::TokenStream> {
+    let infos = placeholder::variant_infos(variants);
+    let mut candidates: Vec<(&syn::Ident, Vec<syn::Pat>)> = Vec::new();
+    for (variant, info) in variants.iter().zip(&infos) {
+        let pats = variant_assocs(variant, &func.sig.ident, assoc_funcs, false)
+            .filter_map(|assoc| match assoc.assoc {
+                AssociationType::Reverse(pat) => Some(placeholder::substitute_pat(pat, info)),
+                _ => None,
+            })
+            .collect::<Result<Vec<syn::Pat>>>()?;
+        if pats.is_empty() {
+            continue;
+        }
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "Reverse associations not allowed for tuple or struct-like variants",
+            ));
+        }
+        candidates.push((&variant.ident, pats));
+    }
+    match multi {
+        Multi::Slice => build_slice(func, &candidates, scrutinee),
+        Multi::Iter | Multi::Collect => {
+            let len = candidates.len();
+            let checks = candidates
+                .iter()
+                .map(|(_, pats)| {
+                    let pats = pats
+                        .iter()
+                        .map(|pat| scrutinee::normalize_pat(&func.options, pat))
+                        .collect::<Result<Vec<proc_macro2::TokenStream>>>()?;
+                    Ok(quote!(::core::matches!(key, #(#pats)|*)))
+                })
+                .collect::<Result<Vec<proc_macro2::TokenStream>>>()?;
+            let arms = candidates
+                .iter()
+                .enumerate()
+                .map(|(index, (var_ident, _))| quote!(#index => Self::#var_ident,));
+            let iter = quote! {
+                <[bool; #len] as ::core::iter::IntoIterator>::into_iter([#(#checks),*])
+                    .enumerate()
+                    .filter(|(_, matched)| *matched)
+                    .map(|(index, _)| match index {
+                        #(#arms)*
+                        _ => ::core::unreachable!(),
+                    })
+            };
+            let iter = if matches!(multi, Multi::Collect) {
+                quote!(::core::iter::Iterator::collect(#iter))
+            } else {
+                iter
+            };
+            Ok(quote! {
+                let key = #scrutinee;
+                #iter
+            })
+        }
+    }
+}
+
+/// Builds a match with an arm per distinct pattern, each of which yields a
+/// static slice of the variants it matches. Wildcards match every key, literals
+/// are told apart by their values and paths by their tokens, so only those are
+/// supported.
+fn build_slice(
+    func: &DeriveFunc,
+    candidates: &[(&syn::Ident, Vec<syn::Pat>)],
+    scrutinee: proc_macro2::TokenStream,
+) -> Result<proc_macro2::TokenStream> {
+    let mut keys: Vec<(String, proc_macro2::TokenStream, Vec<usize>)> = Vec::new();
+    let mut wildcards: Vec<usize> = Vec::new();
+    for (index, (_, pats)) in candidates.iter().enumerate() {
+        for case in pats.iter().flat_map(scrutinee::pat_cases) {
+            match case {
+                syn::Pat::Wild(_) => wildcards.push(index),
+                syn::Pat::Lit(_) | syn::Pat::Path(_) => {
+                    let tokens = scrutinee::normalize_pat(&func.options, case)?;
+                    let key = match case {
+                        syn::Pat::Lit(pat_lit) => literal_key(&func.options, &pat_lit.lit)?,
+                        _ => tokens.to_string(),
+                    };
+                    match keys.iter_mut().find(|(other, _, _)| *other == key) {
+                        Some((_, _, indices)) => indices.push(index),
+                        None => keys.push((key, tokens, vec![index])),
+                    }
+                }
+                case => {
+                    return Err(Error::new_spanned(
+                        case,
+                        "Functions returning `&'static [Self]` only support literal, path and wildcard patterns, consider `Vec<Self>` or `impl Iterator<Item = Self>`",
+                    ));
+                }
+            }
+        }
+    }
+    let values = |mut indices: Vec<usize>| {
+        indices.extend(&wildcards);
+        indices.sort_unstable();
+        indices.dedup();
+        let idents = indices.into_iter().map(|index| candidates[index].0);
+        quote!(&[#(Self::#idents),*])
+    };
+    let arms = keys.into_iter().map(|(_, pat, indices)| {
+        let values = values(indices);
+        quote!(#pat => #values,)
+    });
+    let fallback = values(Vec::new());
+    Ok(quote! {
+        match #scrutinee {
+            #(#arms)*
+            _ => #fallback,
+        }
+    })
+}
+
+/// The value of a literal key, so that keys written differently but equal, such
+/// as `1` and `0x01`, share an arm
+fn literal_key(options: &FuncOptions, lit: &syn::Lit) -> Result<String> {
+    if let Some(bytes) = scrutinee::normalize_literal(options, lit) {
+        return Ok(format!("bytes {:?}", bytes));
+    }
+    Ok(match lit {
+        syn::Lit::CStr(lit) => format!("c string {:?}", lit.value()),
+        syn::Lit::Byte(lit) => format!("byte {}", lit.value()),
+        syn::Lit::Char(lit) => format!("char {:?}", lit.value()),
+        syn::Lit::Int(lit) => match lit.base10_parse::<u128>() {
+            Ok(value) => format!("int {}", value),
+            Err(_) => format!("int {}", lit.base10_parse::<i128>()?),
+        },
+        syn::Lit::Bool(lit) => format!("bool {}", lit.value),
+        lit => {
+            return Err(Error::new_spanned(
+                lit,
+                "Functions returning `&'static [Self]` cannot compare this literal with other keys, consider `Vec<Self>` or `impl Iterator<Item = Self>`",
+            ));
+        }
+    })
+}
@@ -0,0 +1,141 @@
+//! Placeholders for metadata of the variant an association belongs to, of form
+//! `variant!(kind)`. These are replaced in `assoc` values, reverse patterns and
+//! function defaults when building the arm for each variant.
+
+use proc_macro2::{Delimiter, Group, Literal, TokenStream, TokenTree};
+use quote::quote;
+use syn::{Error, Result, Variant, ext::IdentExt};
+
+use crate::{builtin, to_snake_case};
+
+const PLACEHOLDER_MACRO: &str = "variant";
+
+/// The metadata of a variant which placeholders are replaced with
+pub(crate) struct VariantInfo {
+    name: String,
+    index: usize,
+    discriminant: TokenStream,
+}
+
+/// The metadata of every variant, in declaration order
+pub(crate) fn variant_infos(variants: &[&Variant]) -> Vec<VariantInfo> {
+    variants
+        .iter()
+        .zip(builtin::discriminants(variants))
+        .enumerate()
+        .map(|(index, (variant, discriminant))| VariantInfo {
+            name: variant.ident.unraw().to_string(),
+            index,
+            discriminant,
+        })
+        .collect()
+}
+
+/// Replaces every placeholder within an expression
+pub(crate) fn substitute(tokens: TokenStream, info: &VariantInfo) -> Result<TokenStream> {
+    substitute_tokens(tokens, info, false)
+}
+
+/// Replaces every placeholder within a pattern, where only literals may be
+/// substituted
+pub(crate) fn substitute_pat(pat: syn::Pat, info: &VariantInfo) -> Result<syn::Pat> {
+    let tokens = quote!(#pat);
+    if !contains_placeholder(tokens.clone()) {
+        return Ok(pat);
+    }
+    let tokens = substitute_tokens(tokens, info, true)?;
+    syn::parse::Parser::parse2(syn::Pat::parse_multi_with_leading_vert, tokens)
+}
+
+fn contains_placeholder(tokens: TokenStream) -> bool {
+    let mut prev_ident = false;
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => {
+            prev_ident = ident == PLACEHOLDER_MACRO;
+            false
+        }
+        TokenTree::Punct(punct) => punct.as_char() == '!' && std::mem::take(&mut prev_ident),
+        TokenTree::Group(group) => {
+            prev_ident = false;
+            contains_placeholder(group.stream())
+        }
+        TokenTree::Literal(_) => {
+            prev_ident = false;
+            false
+        }
+    })
+}
+
+fn substitute_tokens(
+    tokens: TokenStream,
+    info: &VariantInfo,
+    in_pattern: bool,
+) -> Result<TokenStream> {
+    let mut result = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident == PLACEHOLDER_MACRO => {
+                let is_macro = tokens.peek().is_some_and(
+                    |next| matches!(next, TokenTree::Punct(punct) if punct.as_char() == '!'),
+                );
+                if !is_macro {
+                    result.push(TokenTree::Ident(ident));
+                    continue;
+                }
+                let bang = tokens.next().expect("Peeked token");
+                let Some(TokenTree::Group(args)) = tokens.next() else {
+                    return Err(Error::new_spanned(
+                        quote!(#ident #bang),
+                        "Expected `variant!(kind)`",
+                    ));
+                };
+                result.extend(build_placeholder(&args, info, in_pattern)?);
+            }
+            TokenTree::Group(group) => {
+                let stream = substitute_tokens(group.stream(), info, in_pattern)?;
+                let mut new_group = Group::new(group.delimiter(), stream);
+                new_group.set_span(group.span());
+                result.push(TokenTree::Group(new_group));
+            }
+            token => result.push(token),
+        }
+    }
+    Ok(result.into_iter().collect())
+}
+
+/// The value of a placeholder, given the arguments of `variant!(...)`
+fn build_placeholder(args: &Group, info: &VariantInfo, in_pattern: bool) -> Result<TokenStream> {
+    let kind = syn::parse2::<syn::Ident>(args.stream())
+        .map_err(|_| Error::new(args.span(), "Expected `variant!(kind)`"))?;
+    let string = |value: String| {
+        let mut lit = Literal::string(&value);
+        lit.set_span(kind.span());
+        TokenTree::Literal(lit).into()
+    };
+    match kind.to_string().as_str() {
+        "name" => Ok(string(info.name.clone())),
+        "snake_case" => Ok(string(to_snake_case(&info.name))),
+        "kebab_case" => Ok(string(to_snake_case(&info.name).replace('_', "-"))),
+        "index" => {
+            let mut lit = Literal::usize_unsuffixed(info.index);
+            lit.set_span(kind.span());
+            Ok(TokenTree::Literal(lit).into())
+        }
+        "discriminant" if in_pattern => Err(Error::new_spanned(
+            &kind,
+            "`variant!(discriminant)` cannot be used in patterns",
+        )),
+        "discriminant" => {
+            let discriminant = &info.discriminant;
+            Ok(TokenTree::Group(Group::new(Delimiter::Parenthesis, quote!(#discriminant))).into())
+        }
+        _ => Err(Error::new_spanned(
+            &kind,
+            format!(
+                "Unknown placeholder `{}`, expected `name`, `snake_case`, `kebab_case`, `index` or `discriminant`",
+                kind
+            ),
+        )),
+    }
+}
@@ -0,0 +1,134 @@
+//! Transformations of the value a reverse function matches on, enabled with
+//! the `match`, `trim` and `ascii_case_insensitive` function options. Custom
+//! `match` expressions are used as written, while the built-in modes normalize
+//! both the input and the string literal patterns, the latter at compile time.
+
+use quote::quote;
+use syn::{Error, Result, Variant};
+
+use crate::{AssociationType, FuncOptions, variant_assocs};
+
+/// Whether string literal patterns are normalized
+pub(crate) fn normalizes(options: &FuncOptions) -> bool {
+    options.trim || options.ascii_case_insensitive
+}
+
+/// Builds the value a reverse function matches on, from the default scrutinee
+/// of its parameters. When `bytes` is set, the patterns are byte strings.
+pub(crate) fn build_scrutinee(
+    options: &FuncOptions,
+    default: proc_macro2::TokenStream,
+    bytes: bool,
+) -> proc_macro2::TokenStream {
+    let scrutinee = match &options.scrutinee {
+        Some(expr) => quote!(#expr),
+        None => default,
+    };
+    match (options.trim, bytes) {
+        (false, _) => scrutinee,
+        (true, false) => quote!(::core::primitive::str::trim(#scrutinee)),
+        (true, true) => quote!(<[u8]>::trim_ascii(#scrutinee)),
+    }
+}
+
+/// Whether any reverse association of a function is a byte string literal,
+/// which decides how the input is normalized
+pub(crate) fn has_byte_literals(
+    variants: &[&Variant],
+    func_ident: &syn::Ident,
+    assoc_funcs: &[syn::Ident],
+) -> bool {
+    variants.iter().any(|variant| {
+        variant_assocs(variant, func_ident, assoc_funcs, false).any(|assoc| match assoc.assoc {
+            AssociationType::Reverse(pat) => pat_cases(&pat).into_iter().any(|case| {
+                matches!(
+                    case,
+                    syn::Pat::Lit(syn::PatLit {
+                        lit: syn::Lit::ByteStr(_),
+                        ..
+                    })
+                )
+            }),
+            _ => false,
+        })
+    })
+}
+
+/// The normalized bytes of a string or byte string literal, or `None` for any
+/// other literal
+pub(crate) fn normalize_literal(options: &FuncOptions, lit: &syn::Lit) -> Option<Vec<u8>> {
+    let mut bytes = match lit {
+        syn::Lit::Str(lit) if options.trim => lit.value().trim().as_bytes().to_vec(),
+        syn::Lit::Str(lit) => lit.value().into_bytes(),
+        syn::Lit::ByteStr(lit) if options.trim => lit.value().trim_ascii().to_vec(),
+        syn::Lit::ByteStr(lit) => lit.value(),
+        _ => return None,
+    };
+    if options.ascii_case_insensitive {
+        bytes.make_ascii_lowercase();
+    }
+    Some(bytes)
+}
+
+/// Builds a slice pattern matching the given bytes. Letters match in either
+/// case when `ascii_case_insensitive` is set.
+pub(crate) fn build_bytes_pat(options: &FuncOptions, bytes: &[u8]) -> proc_macro2::TokenStream {
+    let bytes = bytes.iter().map(|byte| {
+        let lower = proc_macro2::Literal::u8_unsuffixed(*byte);
+        if options.ascii_case_insensitive && byte.is_ascii_lowercase() {
+            let upper = proc_macro2::Literal::u8_unsuffixed(byte.to_ascii_uppercase());
+            quote!(#lower | #upper)
+        } else {
+            quote!(#lower)
+        }
+    });
+    quote!([#(#bytes),*])
+}
+
+/// Rewrites a reverse association pattern to match the normalized input. With
+/// `trim`, string literals are trimmed. With `ascii_case_insensitive`, the
+/// input is matched as bytes, so every literal becomes a slice pattern.
+pub(crate) fn normalize_pat(
+    options: &FuncOptions,
+    pat: &syn::Pat,
+) -> Result<proc_macro2::TokenStream> {
+    if !normalizes(options) {
+        return Ok(quote!(#pat));
+    }
+    let cases = pat_cases(pat)
+        .into_iter()
+        .map(|case| match case {
+            syn::Pat::Wild(_) => Ok(quote!(#case)),
+            syn::Pat::Lit(syn::PatLit { lit, .. }) => {
+                let bytes = normalize_literal(options, lit).ok_or_else(|| {
+                    Error::new_spanned(lit, "Expected a string or byte string literal")
+                })?;
+                Ok(match lit {
+                    _ if options.ascii_case_insensitive => build_bytes_pat(options, &bytes),
+                    syn::Lit::Str(lit) => {
+                        let value = String::from_utf8(bytes).expect("Trimmed string is UTF-8");
+                        let lit = syn::LitStr::new(&value, lit.span());
+                        quote!(#lit)
+                    }
+                    lit => {
+                        let lit = syn::LitByteStr::new(&bytes, lit.span());
+                        quote!(#lit)
+                    }
+                })
+            }
+            _ => Err(Error::new_spanned(
+                case,
+                "`trim` and `ascii_case_insensitive` require string or byte string literal patterns",
+            )),
+        })
+        .collect::<Result<Vec<proc_macro2::TokenStream>>>()?;
+    Ok(quote!(#(#cases)|*))
+}
+
+/// The cases of a pattern, which are its alternatives if it is an or-pattern
+pub(crate) fn pat_cases(pat: &syn::Pat) -> Vec<&syn::Pat> {
+    match pat {
+        syn::Pat::Or(pat_or) => pat_or.cases.iter().collect(),
+        pat => vec![pat],
+    }
+}
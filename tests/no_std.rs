@@ -0,0 +1,120 @@
+//! The generated code must only refer to `core`, and must not be confused by
+//! items shadowing the prelude
+
+#![no_std]
+#![allow(dead_code, non_upper_case_globals)]
+
+use enum_assoc::{Assoc, assoc_enum};
+
+const None: u8 = 0;
+struct Some;
+mod str {}
+
+#[derive(Assoc, Debug, PartialEq)]
+#[assoc_builtin(
+    discriminant,
+    from_discriminant,
+    variant_name,
+    index,
+    from_index,
+    count
+)]
+#[assoc_type(Payload = ())]
+#[assoc_map(Kind, to = kind, from = from_kind)]
+#[assoc_set]
+#[assoc_table]
+#[assoc_meta]
+#[func(pub const fn code(&self) -> u8, consts)]
+#[func(pub fn label(&self) -> ::core::option::Option<&'static str>)]
+#[func(pub fn from_code(code: u8) -> ::core::option::Option<Self>)]
+#[func(pub fn from_name(name: &str) -> ::core::option::Option<Self>, lookup = trie)]
+#[func(pub fn from_key(key: &str) -> ::core::option::Option<Self>, lookup = phf, trim)]
+#[func(pub fn from_upper(name: &str) -> ::core::option::Option<Self>, ascii_case_insensitive)]
+enum Token {
+    #[assoc(code = 1, label = variant!(snake_case), from_code = 1, kind = Kind::Word)]
+    #[assoc(from_name = "word", from_key = "w", from_upper = "word")]
+    Word,
+    #[assoc(code = 2, from_code = 2 | 3, from_name = "num", from_key = "n")]
+    #[assoc(from_upper = "num")]
+    Number,
+}
+
+#[derive(Assoc, Debug)]
+#[assoc_error(message = message)]
+#[func(const fn message(&self) -> &'static str)]
+enum Failure {
+    #[assoc(message = "unexpected token")]
+    Unexpected,
+}
+
+/// A fixed-size buffer to format into without an allocator
+struct Buffer {
+    bytes: [u8; 32],
+    len: usize,
+}
+
+impl ::core::fmt::Write for Buffer {
+    fn write_str(&mut self, s: &str) -> ::core::fmt::Result {
+        let end = self.len + s.len();
+        self.bytes
+            .get_mut(self.len..end)
+            .ok_or(::core::fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        ::core::result::Result::Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Word,
+}
+
+assoc_enum! {
+    #[derive(Debug, PartialEq)]
+    enum Op(fn symbol(&self) -> char, fn parse(c: char) -> ::core::option::Option<Self>) {
+        Add => '+', '+';
+        Sub => '-', '-';
+    }
+}
+
+#[test]
+fn test_no_std() {
+    use ::core::option::Option::{None, Some};
+
+    assert_eq!(Token::WORD_CODE, 1);
+    assert_eq!(Token::Number.code(), 2);
+    assert_eq!(Token::Word.label(), Some("word"));
+    assert_eq!(Token::Number.label(), None);
+    assert_eq!(Token::from_code(3), Some(Token::Number));
+    assert_eq!(Token::from_code(4), None);
+    assert_eq!(Token::from_name("num"), Some(Token::Number));
+    assert_eq!(Token::from_key(" w "), Some(Token::Word));
+    assert_eq!(Token::from_key("x"), None);
+    assert_eq!(Token::from_upper("NUM"), Some(Token::Number));
+    assert_eq!(Token::from_discriminant(1), Some(Token::Number));
+    assert_eq!(Token::from_index(0), Some(Token::Word));
+    assert_eq!(Token::Number.variant_name(), "Number");
+    assert_eq!(Token::COUNT, 2);
+    assert_eq!(Token::Word.kind(), Some(Kind::Word));
+    assert_eq!(Token::from_kind(Kind::Word), Some(Token::Word));
+    assert_eq!(Op::Sub.symbol(), '-');
+    assert_eq!(Op::parse('+'), Some(Op::Add));
+    assert_eq!(TokenSet::all().iter().last(), Some(Token::Number));
+    assert_eq!(TokenMap::from_assoc(Token::code)[Token::Number], 2);
+    assert!(::core::error::Error::source(&Failure::Unexpected).is_none());
+
+    let meta = Token::Word.assoc_meta("label").unwrap();
+    assert_eq!(meta.variant, "Word");
+    assert_eq!(meta.tokens, "variant! (snake_case)");
+    let mut buffer = Buffer {
+        bytes: [0; 32],
+        len: 0,
+    };
+    assert_eq!(
+        (meta.format)(&Token::Word, &mut buffer),
+        Some(::core::result::Result::Ok(()))
+    );
+    assert_eq!(&buffer.bytes[..buffer.len], b"Some(\"word\")");
+    assert_eq!(Token::ASSOC_META.len(), 11);
+}
@@ -1,4 +1,4 @@
-use enum_assoc::Assoc;
+use enum_assoc::{Assoc, assoc_enum, assoc_source};
 
 // A bit of mock data
 const WA: &str = "wa";
@@ -186,3 +186,1189 @@ fn test_multi_funcs() {
     assert_eq!(TestMultiFunc::Variant.forward(), 10);
     assert_eq!(TestMultiFunc::reverse(10), Some(TestMultiFunc::Variant));
 }
+
+#[derive(Assoc)]
+#[func(pub fn code(&self) -> u8)]
+#[func(pub fn describe(&self, prefix: &str) -> String)]
+#[func(pub fn hint(&self) -> Option<&'static str>)]
+enum InnerDelegateEnum {
+    #[assoc(code = 1, describe = format!("{prefix}one"), hint = "first")]
+    One,
+    #[assoc(code = 2, describe = format!("{prefix}two"))]
+    Two,
+}
+
+#[derive(Assoc)]
+#[func(pub fn code(&self) -> u8)]
+#[func(pub fn describe(&self, prefix: &str) -> String)]
+#[func(pub fn hint(&self) -> Option<&'static str>)]
+enum DelegateEnum {
+    #[assoc(delegate)]
+    Tuple(InnerDelegateEnum),
+    #[assoc(delegate)]
+    #[assoc(code = 0)]
+    Struct { inner: InnerDelegateEnum },
+    #[assoc(delegate(hint), code = 3, describe = prefix.to_string())]
+    Partial(InnerDelegateEnum),
+}
+
+#[test]
+fn test_delegate() {
+    assert_eq!(DelegateEnum::Tuple(InnerDelegateEnum::Two).code(), 2);
+    assert_eq!(
+        DelegateEnum::Tuple(InnerDelegateEnum::One).describe("#"),
+        "#one"
+    );
+    assert_eq!(
+        DelegateEnum::Tuple(InnerDelegateEnum::One).hint(),
+        Some("first")
+    );
+    assert_eq!(DelegateEnum::Tuple(InnerDelegateEnum::Two).hint(), None);
+    assert_eq!(
+        DelegateEnum::Struct {
+            inner: InnerDelegateEnum::One
+        }
+        .code(),
+        0
+    );
+    assert_eq!(
+        DelegateEnum::Struct {
+            inner: InnerDelegateEnum::Two
+        }
+        .describe("-"),
+        "-two"
+    );
+    assert_eq!(DelegateEnum::Partial(InnerDelegateEnum::One).code(), 3);
+    assert_eq!(
+        DelegateEnum::Partial(InnerDelegateEnum::One).describe("!"),
+        "!"
+    );
+    assert_eq!(
+        DelegateEnum::Partial(InnerDelegateEnum::One).hint(),
+        Some("first")
+    );
+}
+
+#[derive(Assoc)]
+#[func(pub fn id(&self) -> u32)]
+#[func(pub fn name(&self) -> Option<&String>)]
+#[func(pub fn into_name(self) -> Option<String>)]
+enum FieldAssocEnum {
+    A {
+        #[assoc(id)]
+        key: u32,
+        #[assoc(name, into_name)]
+        name: String,
+    },
+    B(#[assoc(id)] u32),
+    #[assoc(id = 0)]
+    C,
+}
+
+#[test]
+fn test_field_assoc() {
+    let a = FieldAssocEnum::A {
+        key: 7,
+        name: "seven".to_string(),
+    };
+    assert_eq!(a.id(), 7);
+    assert_eq!(a.name(), Some(&"seven".to_string()));
+    assert_eq!(a.into_name(), Some("seven".to_string()));
+    assert_eq!(FieldAssocEnum::B(3).id(), 3);
+    assert_eq!(FieldAssocEnum::B(3).name(), None);
+    assert_eq!(FieldAssocEnum::C.id(), 0);
+}
+
+#[derive(Assoc, Debug, PartialEq)]
+#[func(pub const fn code(&self) -> u8, consts)]
+#[func(pub fn label(&self) -> Option<&'static str>, consts)]
+#[func(pub fn weight(&self) -> u16 { 1 }, consts)]
+enum ConstEnum {
+    #[assoc(code = 0x10, label = "read")]
+    Read,
+    #[assoc(code = 0x20, weight = 4)]
+    Write,
+    #[assoc(code = 0x30)]
+    ReadWrite(u8),
+}
+
+#[test]
+fn test_consts() {
+    const READ: u8 = ConstEnum::READ_CODE;
+    assert_eq!(READ, 0x10);
+    assert_eq!(ConstEnum::WRITE_CODE, 0x20);
+    assert_eq!(ConstEnum::READ_WRITE_CODE, 0x30);
+    assert_eq!(ConstEnum::READ_LABEL, Some("read"));
+    assert_eq!(ConstEnum::WRITE_LABEL, None);
+    assert_eq!(ConstEnum::READ_WEIGHT, 1);
+    assert_eq!(ConstEnum::WRITE_WEIGHT, 4);
+    assert_eq!(ConstEnum::Write.code(), 0x20);
+    assert_eq!(ConstEnum::ReadWrite(1).code(), ConstEnum::READ_WRITE_CODE);
+    assert_eq!(ConstEnum::Read.label(), Some("read"));
+    assert_eq!(ConstEnum::Write.weight(), 4);
+    let opcode = 0x20;
+    let variant = match opcode {
+        ConstEnum::READ_CODE => ConstEnum::Read,
+        ConstEnum::WRITE_CODE => ConstEnum::Write,
+        _ => ConstEnum::ReadWrite(opcode),
+    };
+    assert_eq!(variant, ConstEnum::Write);
+}
+
+static STATIC_BUILDS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn build_keywords(keywords: &[&'static str]) -> Vec<&'static str> {
+    STATIC_BUILDS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    keywords.to_vec()
+}
+
+#[derive(Assoc)]
+#[func(pub fn keywords(&self) -> &'static Vec<&'static str>, static)]
+#[func(pub fn lookup(&self) -> Option<&'static std::collections::HashMap<u8, char>>, static)]
+#[func(pub fn fallback(&self) -> &'static String { String::from("none") }, static)]
+enum StaticEnum {
+    #[assoc(keywords = build_keywords(&["fn", "let"]))]
+    #[assoc(lookup = std::collections::HashMap::from([(1, 'a'), (2, 'b')]))]
+    Rust,
+    #[assoc(keywords = build_keywords(&["def"]), fallback = String::from("py"))]
+    Python,
+}
+
+#[test]
+fn test_static() {
+    use std::sync::atomic::Ordering;
+    assert_eq!(STATIC_BUILDS.load(Ordering::SeqCst), 0);
+    assert_eq!(StaticEnum::Rust.keywords(), &vec!["fn", "let"]);
+    assert_eq!(StaticEnum::Rust.keywords(), &vec!["fn", "let"]);
+    assert_eq!(STATIC_BUILDS.load(Ordering::SeqCst), 1);
+    assert_eq!(StaticEnum::Python.keywords(), &vec!["def"]);
+    assert_eq!(STATIC_BUILDS.load(Ordering::SeqCst), 2);
+    assert!(std::ptr::eq(
+        StaticEnum::Rust.keywords(),
+        StaticEnum::Rust.keywords()
+    ));
+    assert_eq!(
+        StaticEnum::Rust.lookup().and_then(|map| map.get(&2)),
+        Some(&'b')
+    );
+    assert!(StaticEnum::Python.lookup().is_none());
+    assert_eq!(StaticEnum::Rust.fallback(), "none");
+    assert_eq!(StaticEnum::Python.fallback(), "py");
+}
+
+#[derive(Assoc)]
+#[assoc_type(Payload, Nullable = bool)]
+#[func(pub fn name(&self) -> &'static str)]
+pub enum ColumnType {
+    #[assoc(Payload = i64, name = "int")]
+    Int,
+    #[assoc(name = "text", Payload = String)]
+    Text,
+    #[assoc(Payload = Vec<u8>, name = "blob")]
+    #[assoc(Nullable = Option<Vec<u8>>)]
+    Blob,
+}
+
+#[test]
+fn test_assoc_type() {
+    use column_type_types::Types;
+
+    fn read<C: Types>(raw: &str) -> C::Payload
+    where
+        C::Payload: std::str::FromStr,
+    {
+        raw.parse().ok().unwrap()
+    }
+    fn size_of_payload<C: Types>(_: C) -> usize {
+        std::mem::size_of::<C::Payload>()
+    }
+
+    let int: i64 = read::<column_type_types::Int>("42");
+    assert_eq!(int, 42);
+    let text: String = read::<column_type_types::Text>("hello");
+    assert_eq!(text, "hello");
+    let blob: <column_type_types::Blob as Types>::Payload = vec![1, 2];
+    let nullable: <column_type_types::Blob as Types>::Nullable = Some(blob);
+    assert_eq!(nullable, Some(vec![1, 2]));
+    let _: <column_type_types::Int as Types>::Nullable = true;
+    assert_eq!(size_of_payload(column_type_types::Int), 8);
+    assert_eq!(std::mem::size_of::<column_type_types::Int>(), 0);
+    assert_eq!(ColumnType::Blob.name(), "blob");
+    assert_eq!(ColumnType::Text.name(), "text");
+}
+
+const WRITE_OP: u8 = 0x20;
+
+#[derive(Assoc, Debug, PartialEq)]
+#[repr(u8)]
+#[assoc_builtin(discriminant, from_discriminant = from_u8)]
+enum ReprEnum {
+    Read = 0x10,
+    Append,
+    Write = WRITE_OP,
+    Seek(u64) = WRITE_OP + 2,
+    Close,
+}
+
+#[derive(Assoc, Debug, PartialEq)]
+#[assoc_builtin(discriminant, from_discriminant)]
+enum ImplicitReprEnum {
+    First,
+    Second,
+    Third = -5,
+    Fourth,
+}
+
+#[derive(Assoc, Debug, PartialEq)]
+#[assoc_builtin(from_discriminant)]
+#[repr(u8)]
+enum Server {
+    HTTPServer = 1,
+    HttpServer = 2,
+}
+
+#[test]
+fn test_discriminant() {
+    const SEEK: u8 = ReprEnum::Seek(0).discriminant();
+    assert_eq!(ReprEnum::Read.discriminant(), 0x10);
+    assert_eq!(ReprEnum::Append.discriminant(), 0x11);
+    assert_eq!(ReprEnum::Write.discriminant(), 0x20);
+    assert_eq!(SEEK, 0x22);
+    assert_eq!(ReprEnum::Close.discriminant(), 0x23);
+    assert_eq!(ReprEnum::from_u8(0x11), Some(ReprEnum::Append));
+    assert_eq!(ReprEnum::from_u8(0x20), Some(ReprEnum::Write));
+    assert_eq!(ReprEnum::from_u8(0x22), None);
+    assert_eq!(ReprEnum::from_u8(0x23), Some(ReprEnum::Close));
+    assert_eq!(ReprEnum::from_u8(0), None);
+    let discriminant: isize = ImplicitReprEnum::Second.discriminant();
+    assert_eq!(discriminant, 1);
+    assert_eq!(ImplicitReprEnum::Fourth.discriminant(), -4);
+    assert_eq!(
+        ImplicitReprEnum::from_discriminant(0),
+        Some(ImplicitReprEnum::First)
+    );
+    assert_eq!(
+        ImplicitReprEnum::from_discriminant(-5),
+        Some(ImplicitReprEnum::Third)
+    );
+    assert_eq!(ImplicitReprEnum::from_discriminant(2), None);
+    assert_eq!(Server::from_discriminant(1), Some(Server::HTTPServer));
+    assert_eq!(Server::from_discriminant(2), Some(Server::HttpServer));
+}
+
+#[derive(Assoc, Debug, PartialEq)]
+#[assoc_builtin(variant_name, index, from_index, count)]
+#[assoc_builtin(variant_name = name, count = LEN)]
+enum Shape {
+    Circle(f32),
+    Square,
+    Rect { w: f32, h: f32 },
+    Point,
+}
+
+#[test]
+fn test_variant_builtins() {
+    const NAME: &str = Shape::Rect { w: 1.0, h: 2.0 }.variant_name();
+    assert_eq!(NAME, "Rect");
+    assert_eq!(Shape::Circle(1.0).name(), "Circle");
+    assert_eq!(Shape::Circle(1.0).index(), 0);
+    assert_eq!(Shape::Point.index(), 3);
+    assert_eq!(Shape::from_index(1), Some(Shape::Square));
+    assert_eq!(Shape::from_index(0), None);
+    assert_eq!(Shape::from_index(3), Some(Shape::Point));
+    assert_eq!(Shape::from_index(4), None);
+    assert_eq!(Shape::COUNT, 4);
+    assert_eq!(Shape::LEN, 4);
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Assoc, Debug, Clone, Copy, PartialEq)]
+#[assoc_builtin(variant_name, from_discriminant)]
+#[assoc_set]
+#[assoc_table]
+#[func(pub const fn r#ref(&self) -> u8, consts)]
+#[func(pub fn label(&self) -> &'static str { variant!(name) })]
+#[repr(u8)]
+enum Keyword {
+    #[assoc(r#ref = 1)]
+    r#type,
+    #[assoc(r#ref = 2)]
+    r#match,
+}
+
+#[test]
+fn test_raw_idents() {
+    assert_eq!(Keyword::r#type.variant_name(), "type");
+    assert_eq!(Keyword::r#match.label(), "match");
+    assert_eq!(Keyword::from_discriminant(1), Some(Keyword::r#match));
+    assert_eq!(Keyword::TYPE_REF, 1);
+    assert_eq!(Keyword::MATCH_REF, 2);
+    assert_eq!(format!("{:?}", KeywordSet::all()), "{type, match}");
+    assert_eq!(
+        format!("{:?}", KeywordMap::from_assoc(Keyword::r#ref)),
+        "{type: 1, match: 2}"
+    );
+}
+
+#[derive(Assoc, Debug, PartialEq)]
+#[func(pub fn label(&self) -> String { format!("{}#{}", variant!(name), variant!(index)) })]
+#[func(pub const fn key(&self) -> &'static str, consts)]
+#[func(pub fn code(&self) -> i32 { variant!(discriminant) * 10 })]
+#[func(pub fn from_key(s: &str) -> Option<Self>)]
+#[func(pub fn from_slug(s: &str) -> Option<Self>, lookup = trie)]
+enum Planet {
+    #[assoc(key = variant!(snake_case), from_key = variant!(snake_case))]
+    #[assoc(from_slug = variant!(kebab_case))]
+    InnerMercury = 1,
+    #[assoc(key = variant!(kebab_case), label = String::from("home"))]
+    #[assoc(from_key = variant!(name) | "earth", from_slug = variant!(kebab_case))]
+    BlueEarth,
+    #[assoc(key = "mars", code = -1)]
+    Mars = 7,
+}
+
+#[test]
+fn test_placeholders() {
+    assert_eq!(Planet::InnerMercury.label(), "InnerMercury#0");
+    assert_eq!(Planet::BlueEarth.label(), "home");
+    assert_eq!(Planet::Mars.label(), "Mars#2");
+    assert_eq!(Planet::INNER_MERCURY_KEY, "inner_mercury");
+    assert_eq!(Planet::BlueEarth.key(), "blue-earth");
+    assert_eq!(Planet::InnerMercury.code(), 10);
+    assert_eq!(Planet::BlueEarth.code(), 20);
+    assert_eq!(Planet::Mars.code(), -1);
+    assert_eq!(
+        Planet::from_key("inner_mercury"),
+        Some(Planet::InnerMercury)
+    );
+    assert_eq!(Planet::from_key("BlueEarth"), Some(Planet::BlueEarth));
+    assert_eq!(Planet::from_key("earth"), Some(Planet::BlueEarth));
+    assert_eq!(Planet::from_key("Mars"), None);
+    assert_eq!(
+        Planet::from_slug("inner-mercury"),
+        Some(Planet::InnerMercury)
+    );
+    assert_eq!(Planet::from_slug("blue-earth"), Some(Planet::BlueEarth));
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum WireOp {
+    Get,
+    Put,
+    Delete,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Tier {
+    Low,
+    High,
+}
+
+#[derive(Assoc, Debug, PartialEq)]
+#[assoc_map(WireOp, to = to_wire, from = from_wire, total)]
+#[assoc_map(Tier, to = level)]
+enum Op {
+    #[assoc(to_wire = WireOp::Get, level = Tier::Low)]
+    Read,
+    #[assoc(to_wire = WireOp::Put, level = Tier::High)]
+    Write,
+    #[assoc(to_wire = WireOp::Delete)]
+    Remove,
+}
+
+#[derive(Assoc, Debug, PartialEq)]
+#[assoc_map(Tier, from = from_level)]
+enum Priority {
+    #[assoc(from_level = Tier::High)]
+    Urgent,
+    Normal,
+}
+
+#[test]
+fn test_assoc_map() {
+    const WIRE: WireOp = Op::Write.to_wire();
+    assert_eq!(WIRE, WireOp::Put);
+    assert_eq!(Op::Remove.to_wire(), WireOp::Delete);
+    for op in [Op::Read, Op::Write, Op::Remove] {
+        assert_eq!(Op::from_wire(op.to_wire()), op);
+    }
+    assert_eq!(Op::Read.level(), Some(Tier::Low));
+    assert_eq!(Op::Remove.level(), None);
+    assert_eq!(Priority::from_level(Tier::High), Some(Priority::Urgent));
+    assert_eq!(Priority::from_level(Tier::Low), None);
+    // No tier maps to a variant without a `from_level` association
+    assert!(
+        [Tier::Low, Tier::High]
+            .into_iter()
+            .all(|tier| Priority::from_level(tier) != Some(Priority::Normal))
+    );
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Category {
+    Audio,
+    Video,
+}
+
+#[derive(Assoc, Debug, PartialEq)]
+#[func(pub fn category(&self) -> Category, pub fn with_category(c: Category) -> &'static [Self])]
+#[func(pub fn with_extension(ext: &str) -> Vec<Self>, ascii_case_insensitive)]
+#[func(pub fn with_rate(rate: u32) -> impl Iterator<Item = Self>)]
+#[func(pub fn with_tag(tag: &str) -> &'static [Self], trim)]
+#[func(pub fn with_code(code: u8) -> &'static [Self])]
+enum Codec {
+    #[assoc(category = Category::Audio, with_extension = "mp3" | "mpga")]
+    #[assoc(with_rate = 0..=48000, with_tag = "lossy", with_code = 1)]
+    Mp3,
+    #[assoc(category = Category::Audio, with_extension = "flac")]
+    #[assoc(with_rate = 0..=192000, with_tag = "lossless" | _, with_code = 2)]
+    Flac,
+    #[assoc(category = Category::Video, with_extension = "ts" | "mp4")]
+    #[assoc(with_tag = " lossy", with_code = 0x01 | 3u8)]
+    H264,
+    #[assoc(category = Category::Video, with_extension = "TS")]
+    Mpeg2,
+}
+
+#[test]
+fn test_multi_reverse() {
+    assert_eq!(
+        Codec::with_category(Category::Audio),
+        &[Codec::Mp3, Codec::Flac]
+    );
+    assert_eq!(
+        Codec::with_category(Codec::H264.category()),
+        &[Codec::H264, Codec::Mpeg2]
+    );
+    assert_eq!(Codec::with_extension("Ts"), vec![Codec::H264, Codec::Mpeg2]);
+    assert_eq!(Codec::with_extension("MPGA"), vec![Codec::Mp3]);
+    assert!(Codec::with_extension("ogg").is_empty());
+    assert_eq!(
+        Codec::with_rate(44100).collect::<Vec<_>>(),
+        vec![Codec::Mp3, Codec::Flac]
+    );
+    assert_eq!(
+        Codec::with_rate(96000).collect::<Vec<_>>(),
+        vec![Codec::Flac]
+    );
+    assert_eq!(Codec::with_rate(200000).next(), None);
+    assert_eq!(Codec::with_code(1), &[Codec::Mp3, Codec::H264]);
+    assert_eq!(Codec::with_code(3), &[Codec::H264]);
+    assert!(Codec::with_code(4).is_empty());
+    assert_eq!(
+        Codec::with_tag(" lossy "),
+        &[Codec::Mp3, Codec::Flac, Codec::H264]
+    );
+    assert_eq!(Codec::with_tag("lossless"), &[Codec::Flac]);
+    assert_eq!(Codec::with_tag("other"), &[Codec::Flac]);
+}
+
+#[derive(Assoc, Debug, PartialEq)]
+#[assoc_set]
+#[func(pub const fn is_admin(&self) -> bool { false })]
+enum Permission {
+    Read,
+    Write,
+    #[assoc(is_admin = true)]
+    Delete,
+    #[assoc(is_admin = true)]
+    Grant,
+}
+
+#[derive(Assoc, Debug, Clone, Copy, PartialEq)]
+#[assoc_set(Flags)]
+enum Flag {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+}
+
+#[test]
+fn test_assoc_set() {
+    const USER: PermissionSet = PermissionSet::new()
+        .with(Permission::Read)
+        .with(Permission::Write);
+    assert_eq!(USER.len(), 2);
+    assert!(USER.contains(&Permission::Read));
+    assert!(!USER.contains(&Permission::Grant));
+    assert_eq!(USER.bits(), 0b0011);
+    let admin = PermissionSet::from_fn(Permission::is_admin);
+    assert_eq!(
+        admin.iter().collect::<Vec<_>>(),
+        vec![Permission::Delete, Permission::Grant]
+    );
+    assert_eq!(format!("{:?}", admin), "{Delete, Grant}");
+    assert_eq!(format!("{:#?}", admin), "{\n    Delete,\n    Grant,\n}");
+    assert_eq!(USER | admin, PermissionSet::all());
+    assert_eq!(!USER, admin);
+    assert!((USER & admin).is_empty());
+    assert!(USER.is_disjoint(&admin));
+    assert!(USER.is_subset(&PermissionSet::all()));
+    assert_eq!(PermissionSet::all() - admin, USER);
+    assert_eq!(
+        (USER ^ PermissionSet::from(Permission::Write))
+            .iter()
+            .next(),
+        Some(Permission::Read)
+    );
+    let mut set: PermissionSet = [Permission::Grant, Permission::Grant].into_iter().collect();
+    assert!(set.insert(Permission::Read));
+    assert!(!set.insert(Permission::Read));
+    assert!(set.remove(&Permission::Grant));
+    assert_eq!(set, PermissionSet::from(Permission::Read));
+    set.clear();
+    assert!(set.is_empty());
+    assert_eq!(PermissionSet::from_bits(0xff), PermissionSet::all());
+
+    let flags = Flags::all().without(Flag::A);
+    assert_eq!(Flags::CAPACITY, 9);
+    assert_eq!(flags.bits(), 0b1_1111_1110u16);
+    assert_eq!(flags.complement().iter().collect::<Vec<_>>(), vec![Flag::A]);
+}
+
+#[derive(Assoc, Debug, PartialEq, Clone, Copy)]
+#[assoc_table]
+#[func(pub const fn default_limit(&self) -> u32)]
+enum Endpoint {
+    #[assoc(default_limit = 100)]
+    Search,
+    #[assoc(default_limit = 10)]
+    Upload,
+    #[assoc(default_limit = 1000)]
+    Status,
+}
+
+#[test]
+fn test_assoc_table() {
+    let mut limits = EndpointMap::from_assoc(Endpoint::default_limit);
+    assert_eq!(limits[Endpoint::Upload], 10);
+    limits[Endpoint::Upload] += 5;
+    assert_eq!(limits.insert(Endpoint::Search, 50), 100);
+    assert_eq!(limits.get(&Endpoint::Search), &50);
+    assert_eq!(
+        limits.iter().collect::<Vec<_>>(),
+        vec![
+            (Endpoint::Search, &50),
+            (Endpoint::Upload, &15),
+            (Endpoint::Status, &1000)
+        ]
+    );
+    assert_eq!(
+        format!("{:?}", limits),
+        "{Search: 50, Upload: 15, Status: 1000}"
+    );
+
+    let mut counters = EndpointMap::<u64>::default();
+    for endpoint in [Endpoint::Status, Endpoint::Status, Endpoint::Search] {
+        counters[&endpoint] += 1;
+    }
+    for (_, count) in counters.iter_mut() {
+        *count *= 2;
+    }
+    assert_eq!(counters.as_array(), &[2, 0, 4]);
+    assert_eq!(counters.values().sum::<u64>(), 6);
+    let labels = counters.map(|endpoint, count| format!("{:?}={}", endpoint, count));
+    assert_eq!(labels[Endpoint::Status], "Status=4");
+    assert_eq!(EndpointMap::<()>::LEN, 3);
+    const FIXED: EndpointMap<u8> = EndpointMap::from_array([1, 2, 3]);
+    assert_eq!(*FIXED.get(&Endpoint::Status), 3);
+}
+
+#[derive(Assoc, Debug, PartialEq, Eq)]
+#[assoc_ord(by = rank)]
+#[func(pub const fn rank(&self) -> u8)]
+enum Severity {
+    #[assoc(rank = 2)]
+    Error,
+    #[assoc(rank = 0)]
+    Info,
+    #[assoc(rank = 1)]
+    Warning,
+    #[assoc(rank = 2)]
+    Fatal,
+}
+
+#[derive(Assoc, Debug)]
+#[assoc_ord(by = rank)]
+#[assoc_eq(by = rank)]
+#[func(pub const fn rank(&self) -> u8)]
+enum Urgency {
+    #[assoc(rank = 1)]
+    High,
+    #[assoc(rank = 1)]
+    Urgent,
+    #[assoc(rank = 0)]
+    Low,
+}
+
+#[derive(Assoc, Debug)]
+#[assoc_eq(u16, by = code)]
+#[assoc_eq(&str, by = reason)]
+#[assoc_eq(by = class)]
+#[func(pub const fn code(&self) -> u16)]
+#[func(pub const fn reason(&self) -> &'static str)]
+#[func(pub const fn class(&self) -> u16 { self.code() / 100 })]
+enum HttpStatus {
+    #[assoc(code = 200, reason = "OK")]
+    Ok,
+    #[assoc(code = 201, reason = "Created")]
+    Created,
+    #[assoc(code = 404, reason = "Not Found")]
+    NotFound,
+}
+
+#[test]
+fn test_assoc_cmp() {
+    assert!(Severity::Error > Severity::Warning);
+    assert!(Severity::Info < Severity::Warning);
+    let mut severities = vec![Severity::Warning, Severity::Error, Severity::Info];
+    severities.sort();
+    assert_eq!(
+        severities,
+        vec![Severity::Info, Severity::Warning, Severity::Error]
+    );
+    assert_eq!(severities.iter().max(), Some(&Severity::Error));
+    assert!(Severity::Error < Severity::Fatal);
+    assert_eq!(
+        Severity::Fatal.cmp(&Severity::Fatal),
+        std::cmp::Ordering::Equal
+    );
+    assert_ne!(Severity::Error, Severity::Fatal);
+    assert_eq!(
+        Urgency::High.cmp(&Urgency::Urgent),
+        std::cmp::Ordering::Equal
+    );
+    assert_eq!(Urgency::High, Urgency::Urgent);
+    assert!(Urgency::Low < Urgency::Urgent);
+
+    assert!(HttpStatus::NotFound == 404u16);
+    assert!(404u16 == HttpStatus::NotFound);
+    assert!(HttpStatus::Ok != 201u16);
+    assert!(HttpStatus::Created == "Created");
+    assert!("Not Found" == HttpStatus::NotFound);
+    assert_eq!(HttpStatus::Ok, HttpStatus::Created);
+    assert_ne!(HttpStatus::Ok, HttpStatus::NotFound);
+    let classes = [HttpStatus::Ok, HttpStatus::Created, HttpStatus::NotFound]
+        .into_iter()
+        .collect::<std::collections::HashSet<_>>();
+    assert_eq!(classes.len(), 2);
+}
+
+#[derive(Assoc, Debug)]
+#[assoc_error(message = message, source = cause)]
+#[func(pub fn message(&self) -> String)]
+enum ConfigError {
+    #[assoc(message = format!("cannot read {}", _path))]
+    Read { path: String, cause: std::io::Error },
+    #[assoc(message = format!("invalid value at line {_0}"))]
+    Parse(usize, std::num::ParseIntError),
+    #[assoc(message = "missing config".to_string())]
+    Missing,
+}
+
+#[derive(Assoc, Debug)]
+#[assoc_error(message = message, source = cause)]
+#[func(pub const fn message(&self) -> &'static str)]
+#[func(pub fn cause(&self) -> Option<&std::num::ParseIntError>)]
+enum StartupError {
+    #[assoc(message = "bad config")]
+    Config(#[assoc(cause)] std::num::ParseIntError),
+    #[assoc(message = "already running")]
+    Running,
+}
+
+#[derive(Assoc, Debug)]
+#[assoc_error(message = message, source = 1)]
+#[func(pub const fn message(&self) -> &'static str)]
+enum WrappedError {
+    #[assoc(message = "wrapped")]
+    Wrapped(u8, ConfigError),
+    #[assoc(message = "plain")]
+    Plain,
+    #[assoc(message = "boxed")]
+    Boxed(u8, Box<dyn std::error::Error + Send + Sync>),
+}
+
+#[test]
+fn test_assoc_error() {
+    use std::error::Error;
+    let parse_error = "x".parse::<u8>().unwrap_err();
+    let read = ConfigError::Read {
+        path: "app.toml".to_string(),
+        cause: std::io::Error::other("denied"),
+    };
+    assert_eq!(read.to_string(), "cannot read app.toml");
+    assert_eq!(read.source().unwrap().to_string(), "denied");
+    let parse = ConfigError::Parse(3, parse_error.clone());
+    assert_eq!(parse.to_string(), "invalid value at line 3");
+    assert!(parse.source().is_none());
+    assert_eq!(format!("{:>16}", ConfigError::Missing), "  missing config");
+    assert!(ConfigError::Missing.source().is_none());
+
+    let config = StartupError::Config(parse_error.clone());
+    assert_eq!(config.to_string(), "bad config");
+    assert!(config.source().unwrap().is::<std::num::ParseIntError>());
+    assert!(StartupError::Running.source().is_none());
+
+    let wrapped = WrappedError::Wrapped(0, ConfigError::Missing);
+    assert_eq!(wrapped.source().unwrap().to_string(), "missing config");
+    assert!(WrappedError::Plain.source().is_none());
+    let boxed = WrappedError::Boxed(0, Box::new(ConfigError::Missing));
+    assert_eq!(boxed.source().unwrap().to_string(), "missing config");
+    assert!(boxed.source().unwrap().is::<ConfigError>());
+    let boxed: Box<dyn Error> = Box::new(wrapped);
+    assert_eq!(boxed.to_string(), "wrapped");
+}
+
+#[derive(Clone, Copy)]
+enum Lang {
+    En,
+    Fr,
+    De,
+}
+
+#[derive(Clone, Copy)]
+enum Platform {
+    Linux,
+    Windows,
+}
+
+#[derive(Assoc)]
+#[func(pub fn label(&self, lang: Lang) -> &'static str)]
+#[func(pub fn hint(&self, lang: Lang) -> Option<&'static str>)]
+#[func(pub fn shortcut(&self, platform: Platform, shift: bool) -> String { String::new() })]
+#[func(pub const fn code(&self) -> u8)]
+enum MenuItem {
+    #[assoc(label(Lang::En) = "Open", label(Lang::Fr) = "Ouvrir", label(_) = "Open")]
+    #[assoc(hint(Lang::En | Lang::De) = "Opens a file", code = 1)]
+    #[assoc(shortcut(Platform::Linux, false) = "Ctrl+O".to_string())]
+    #[assoc(shortcut(Platform::Windows, _) = format!("Ctrl+{}", variant!(index)))]
+    Open,
+    #[assoc(label(Lang::En) = "Quit", label(Lang::Fr) = "Quitter", label(Lang::De) = "Beenden")]
+    #[assoc(hint(Lang::En | Lang::Fr | Lang::De) = "Quits", code = 2)]
+    Quit,
+}
+
+#[test]
+fn test_keyed_assoc() {
+    assert_eq!(MenuItem::Open.label(Lang::En), "Open");
+    assert_eq!(MenuItem::Open.label(Lang::Fr), "Ouvrir");
+    assert_eq!(MenuItem::Open.label(Lang::De), "Open");
+    assert_eq!(MenuItem::Quit.label(Lang::De), "Beenden");
+    assert_eq!(MenuItem::Open.hint(Lang::De), Some("Opens a file"));
+    assert_eq!(MenuItem::Open.hint(Lang::Fr), None);
+    assert_eq!(MenuItem::Quit.hint(Lang::En), Some("Quits"));
+    assert_eq!(MenuItem::Open.shortcut(Platform::Linux, false), "Ctrl+O");
+    assert_eq!(MenuItem::Open.shortcut(Platform::Linux, true), "");
+    assert_eq!(MenuItem::Open.shortcut(Platform::Windows, true), "Ctrl+0");
+    assert_eq!(MenuItem::Quit.shortcut(Platform::Windows, false), "");
+    assert_eq!(MenuItem::Quit.code(), 2);
+}
+
+#[derive(Debug, PartialEq)]
+enum JobEvent {
+    Start,
+    Pause,
+    Finish,
+    Fail,
+}
+
+#[derive(Assoc, Debug, PartialEq)]
+#[assoc_state(JobEvent, terminal(Done, Failed))]
+#[func(pub const fn label(&self) -> &'static str)]
+enum Job {
+    #[assoc(on(JobEvent::Start) = Running, on(JobEvent::Fail) = Failed, label = "queued")]
+    Queued,
+    #[assoc(on(JobEvent::Pause) = Self::Paused, on(JobEvent::Finish) = Done)]
+    #[assoc(on(JobEvent::Fail) = Failed, label = "running")]
+    Running,
+    #[assoc(on(JobEvent::Start | JobEvent::Finish) = Running, label = "paused")]
+    Paused,
+    #[assoc(label = "done")]
+    Done,
+    #[assoc(label = "failed")]
+    Failed,
+}
+
+#[derive(Assoc, Debug, PartialEq)]
+#[assoc_state(bool, on = next)]
+enum Light {
+    #[assoc(next(true) = On)]
+    Off,
+    #[assoc(next(false) = Off)]
+    On,
+}
+
+#[test]
+fn test_assoc_state() {
+    const NEXT: Option<Job> = Job::Queued.transition(&JobEvent::Start);
+    assert_eq!(NEXT, Some(Job::Running));
+    assert_eq!(Job::Queued.transition(&JobEvent::Pause), None);
+    assert_eq!(Job::Running.transition(&JobEvent::Pause), Some(Job::Paused));
+    assert_eq!(
+        Job::Paused.transition(&JobEvent::Finish),
+        Some(Job::Running)
+    );
+    assert_eq!(Job::Done.transition(&JobEvent::Start), None);
+    assert_eq!(
+        Job::Running.events(),
+        &[JobEvent::Pause, JobEvent::Finish, JobEvent::Fail]
+    );
+    assert_eq!(Job::Paused.events(), &[JobEvent::Start, JobEvent::Finish]);
+    assert!(Job::Failed.events().is_empty());
+    assert!(Job::Done.is_terminal());
+    assert!(!Job::Paused.is_terminal());
+    assert_eq!(Job::Running.label(), "running");
+
+    let mut job = Job::Queued;
+    for event in [
+        JobEvent::Start,
+        JobEvent::Pause,
+        JobEvent::Start,
+        JobEvent::Finish,
+    ] {
+        job = job.transition(&event).unwrap();
+    }
+    assert_eq!(job, Job::Done);
+
+    assert_eq!(Light::Off.transition(&true), Some(Light::On));
+    assert_eq!(Light::Off.transition(&false), None);
+    assert_eq!(Light::On.events(), &[false]);
+    assert!(!Light::On.is_terminal());
+}
+
+#[derive(Assoc, Debug, PartialEq)]
+#[assoc_macro(export, opcode, mnemonic, operand)]
+#[func(pub const fn opcode(&self) -> u8)]
+#[func(pub const fn mnemonic(&self) -> &'static str { variant!(snake_case) })]
+#[func(pub const fn operand(&self) -> Option<&'static str>)]
+enum Instruction {
+    #[assoc(opcode = 0x10, mnemonic = "ld", operand = "addr")]
+    Load,
+    #[assoc(opcode = 0x11, operand = "addr")]
+    Store,
+    #[assoc(opcode = 0x10 | variant!(index))]
+    Halt,
+}
+
+macro_rules! opcode_const {
+    ($variant:ident, $opcode:expr, $mnemonic:expr, $operand:expr) => {
+        assert_eq!(Instruction::$variant.opcode(), $opcode);
+        assert_eq!(Instruction::$variant.mnemonic(), $mnemonic);
+        assert_eq!(Instruction::$variant.operand(), $operand);
+    };
+}
+
+mod registry {
+    macro_rules! check_opcode {
+        ($variant:ident, $opcode:expr, $($rest:expr),*) => {
+            assert!($opcode < 0x20, stringify!($variant));
+        };
+    }
+    pub(crate) use check_opcode;
+}
+
+#[test]
+fn test_assoc_macro() {
+    instruction_variants!(opcode_const);
+    instruction_variants!(registry::check_opcode);
+
+    let mut entries = Vec::new();
+    macro_rules! collect {
+        ($variant:ident, $opcode:expr, $($rest:expr),*) => {
+            entries.push((stringify!($variant), $opcode))
+        };
+    }
+    instruction_variants!(collect);
+    assert_eq!(entries, [("Load", 0x10), ("Store", 0x11), ("Halt", 0x12)]);
+
+    let mut count = 0;
+    macro_rules! count {
+        ($($args:tt)*) => {
+            count += 1
+        };
+    }
+    instruction_variants!(count);
+    assert_eq!(count, 3);
+}
+
+macro_rules! lookup_enum {
+    ($name:ident, $($lookup:tt)*) => {
+        #[derive(Assoc, Debug, PartialEq)]
+        #[func(pub fn from_name(s: &str) -> Option<Self> $($lookup)*)]
+        #[func(pub const fn from_bytes(b: &[u8]) -> Self $($lookup)*)]
+        enum $name {
+            #[assoc(from_name = "read" | "r", from_bytes = b"read")]
+            Read,
+            #[assoc(from_name = "write", from_bytes = _)]
+            Write,
+            #[assoc(from_name = "", from_bytes = b"")]
+            Empty,
+            #[assoc(from_name = "rea", from_bytes = b"\xff\x00")]
+            Prefix,
+        }
+    };
+}
+
+lookup_enum!(LookupMatch,);
+lookup_enum!(LookupTrie, , lookup = trie);
+lookup_enum!(LookupPhf, , lookup = phf);
+
+#[test]
+fn test_lookup() {
+    macro_rules! check {
+        ($name:ident) => {{
+            const READ: $name = $name::from_bytes(b"read");
+            assert_eq!(READ, $name::Read);
+            assert_eq!($name::from_name("read"), Some($name::Read));
+            assert_eq!($name::from_name("r"), Some($name::Read));
+            assert_eq!($name::from_name("write"), Some($name::Write));
+            assert_eq!($name::from_name(""), Some($name::Empty));
+            assert_eq!($name::from_name("rea"), Some($name::Prefix));
+            assert_eq!($name::from_name("reads"), None);
+            assert_eq!($name::from_name("READ"), None);
+            assert_eq!($name::from_bytes(b"read"), $name::Read);
+            assert_eq!($name::from_bytes(b""), $name::Empty);
+            assert_eq!($name::from_bytes(&[0xff, 0]), $name::Prefix);
+            assert_eq!($name::from_bytes(b"other"), $name::Write);
+        }};
+    }
+    check!(LookupMatch);
+    check!(LookupTrie);
+    check!(LookupPhf);
+}
+
+macro_rules! case_insensitive_enum {
+    ($name:ident, $($lookup:tt)*) => {
+        #[derive(Assoc, Debug, PartialEq)]
+        #[func(pub fn from_name(s: &str) -> Option<Self>, ascii_case_insensitive, trim $($lookup)*)]
+        #[func(pub const fn from_bytes(b: &[u8]) -> Self, ascii_case_insensitive $($lookup)*)]
+        enum $name {
+            #[assoc(from_name = "Read" | "R", from_bytes = b"READ")]
+            Read,
+            #[assoc(from_name = " write ", from_bytes = _)]
+            Write,
+            #[assoc(from_name = "read-all", from_bytes = b"12-ab")]
+            ReadAll,
+        }
+    };
+}
+
+case_insensitive_enum!(CaseMatch,);
+case_insensitive_enum!(CaseTrie, , lookup = trie);
+case_insensitive_enum!(CasePhf, , lookup = phf);
+
+#[derive(Assoc, Debug, PartialEq)]
+#[func(pub fn from_name(s: &str) -> Option<Self>, match = s.to_lowercase().as_str())]
+#[func(pub fn from_parts(major: u8, minor: u8) -> Option<Self>, match = (major, minor / 10))]
+#[func(pub fn from_key(s: &str) -> Option<Self>, match = s.split_once(':').map_or(s, |(_, key)| key), lookup = trie)]
+enum Greek {
+    #[assoc(from_name = "αλφα", from_parts = (1, 0), from_key = "alpha")]
+    Alpha,
+    #[assoc(from_name = "βητα", from_parts = (1, 1..=2), from_key = "beta")]
+    Beta,
+}
+
+#[test]
+fn test_scrutinee() {
+    macro_rules! check {
+        ($name:ident) => {{
+            const READ: $name = $name::from_bytes(b"rEaD");
+            assert_eq!(READ, $name::Read);
+            assert_eq!($name::from_name("read"), Some($name::Read));
+            assert_eq!($name::from_name("  READ\n"), Some($name::Read));
+            assert_eq!($name::from_name("r"), Some($name::Read));
+            assert_eq!($name::from_name("Write"), Some($name::Write));
+            assert_eq!($name::from_name("Read-All "), Some($name::ReadAll));
+            assert_eq!($name::from_name("read all"), None);
+            assert_eq!($name::from_name("wr"), None);
+            assert_eq!($name::from_bytes(b"12-AB"), $name::ReadAll);
+            assert_eq!($name::from_bytes(b" read"), $name::Write);
+        }};
+    }
+    check!(CaseMatch);
+    check!(CaseTrie);
+    check!(CasePhf);
+    assert_eq!(Greek::from_name("ΑΛΦΑ"), Some(Greek::Alpha));
+    assert_eq!(Greek::from_name("Βητα"), Some(Greek::Beta));
+    assert_eq!(Greek::from_parts(1, 9), Some(Greek::Alpha));
+    assert_eq!(Greek::from_parts(1, 25), Some(Greek::Beta));
+    assert_eq!(Greek::from_parts(2, 0), None);
+    assert_eq!(Greek::from_key("greek:beta"), Some(Greek::Beta));
+    assert_eq!(Greek::from_key("alpha"), Some(Greek::Alpha));
+    assert_eq!(Greek::from_key("greek:gamma"), None);
+}
+
+assoc_enum! {
+    /// Operations of a file
+    #[derive(Debug, PartialEq)]
+    #[assoc_builtin(discriminant)]
+    #[repr(u8)]
+    pub enum FileOp(
+        pub const fn code(&self) -> Option<u8>,
+        pub fn name(&self) -> &'static str { "unknown" },
+        pub fn from_name(name: &str) -> Option<Self>,
+        pub fn length(&self) -> usize,
+    ) {
+        Read = 2 => 0x10, "read", "read" | "r", 4;
+        #[assoc(length = 42)]
+        Write => 0x20, "write", "write", ;
+        Seek(u64) => None, , , _0.to_string().len();
+        Close => , "close", "close", 5,
+    }
+}
+
+#[test]
+fn test_assoc_enum() {
+    assert_eq!(FileOp::Read.code(), Some(0x10));
+    assert_eq!(FileOp::Seek(0).code(), None);
+    assert_eq!(FileOp::Close.code(), None);
+    assert_eq!(FileOp::Write.name(), "write");
+    assert_eq!(FileOp::Seek(1).name(), "unknown");
+    assert_eq!(FileOp::from_name("r"), Some(FileOp::Read));
+    assert_eq!(FileOp::from_name("close"), Some(FileOp::Close));
+    assert_eq!(FileOp::from_name("seek"), None);
+    assert_eq!(FileOp::Read.length(), 4);
+    assert_eq!(FileOp::Write.length(), 42);
+    assert_eq!(FileOp::Seek(12345).length(), 5);
+    assert_eq!(FileOp::Seek(0).discriminant(), 4);
+}
+
+#[assoc_source(
+    path = "tests/data/status.csv",
+    variant = "name",
+    funcs(code = "code", label = "label", from_code = "code", retry = "retry")
+)]
+#[derive(Assoc, Debug, PartialEq)]
+#[func(pub const fn code(&self) -> u16)]
+#[func(pub fn label(&self) -> &'static str)]
+#[func(pub fn from_code(code: u16) -> Option<Self>)]
+#[func(pub fn retry(&self) -> Option<bool>)]
+enum Status {
+    #[assoc(code = 0, label = "Unknown")]
+    Unknown,
+}
+
+#[assoc_source(
+    path = "tests/data/countries.json",
+    variant = "name",
+    funcs(code = "code", dial = "dial", euro = "euro")
+)]
+#[derive(Assoc, Debug, PartialEq)]
+#[func(pub fn code(&self) -> &str)]
+#[func(pub fn dial(&self) -> Option<u16>)]
+#[func(pub fn euro(&self) -> bool { false })]
+enum Country {}
+
+#[assoc_source(
+    path = "tests/data/levels.toml",
+    variant = "name",
+    funcs(severity = "severity", prefix = "prefix")
+)]
+#[derive(Assoc, Debug, PartialEq)]
+#[func(pub fn severity(&self) -> u8)]
+#[func(pub fn prefix(&self) -> Option<&'static str>)]
+enum Level {}
+
+#[test]
+fn test_assoc_source() {
+    assert_eq!(Status::Unknown.code(), 0);
+    assert_eq!(Status::NotFound.code(), 404);
+    assert_eq!(Status::TooManyRequests.label(), "Too Many \"Requests\"");
+    assert_eq!(Status::from_code(200), Some(Status::Ok));
+    assert_eq!(Status::from_code(500), None);
+    assert_eq!(Status::Ok.retry(), None);
+    assert_eq!(Status::TooManyRequests.retry(), Some(true));
+    assert_eq!(Country::France.code(), "FR");
+    assert_eq!(Country::Us.dial(), Some(1));
+    assert_eq!(Country::Antarctica.dial(), None);
+    assert!(Country::Germany.euro());
+    assert!(!Country::Antarctica.euro());
+    assert_eq!(Level::Warn.severity(), 2);
+    assert_eq!(Level::Warn.prefix(), Some("W"));
+    assert_eq!(Level::Info.prefix(), None);
+}
+
+#[derive(Assoc)]
+#[assoc_debug]
+#[func(pub fn debug_code(&self) -> u8 { 0 })]
+enum DebugEnum {
+    #[assoc(debug_code = 1)]
+    First,
+    Second,
+}
+
+#[test]
+fn test_assoc_debug() {
+    assert_eq!(DebugEnum::First.debug_code(), 1);
+    assert_eq!(DebugEnum::Second.debug_code(), 0);
+}
+
+struct Opaque;
+
+#[derive(Assoc, Debug, PartialEq)]
+#[assoc_meta]
+#[func(pub fn label(&self) -> &'static str { "other" })]
+#[func(pub fn weight(&self) -> Option<u32>, pub fn from_weight(w: u32) -> Option<Self>)]
+#[func(pub fn opaque(&self) -> Opaque { Opaque })]
+#[func(pub fn scaled(&self, factor: u32) -> u32 { factor })]
+enum Fruit {
+    #[assoc(label = "Apple", weight = 150)]
+    Apple,
+    #[assoc(weight = 120 + 5)]
+    Pear {
+        ripe: bool,
+    },
+    Plum(#[assoc(weight)] u32),
+}
+
+#[test]
+fn test_assoc_meta() {
+    let entries = Fruit::ASSOC_META
+        .iter()
+        .map(|meta| (meta.variant, meta.func, meta.tokens))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        [
+            ("Apple", "label", "\"Apple\""),
+            ("Apple", "weight", "150"),
+            ("Apple", "from_weight", "150"),
+            ("Pear", "weight", "120 + 5"),
+            ("Plum", "weight", "_0"),
+        ]
+    );
+    let format = |meta: &fruit_meta::AssocMeta<Fruit>, value: &Fruit| {
+        let mut out = String::new();
+        (meta.format)(value, &mut out).map(|result| result.map(|()| out))
+    };
+    let meta = Fruit::Pear { ripe: true }.assoc_meta("weight").unwrap();
+    assert_eq!(meta.tokens, "120 + 5");
+    assert_eq!(
+        format(meta, &Fruit::Pear { ripe: true }),
+        Some(Ok("Some(125)".to_string()))
+    );
+    let meta = Fruit::Apple.assoc_meta("label").unwrap();
+    assert_eq!(
+        format(meta, &Fruit::Apple),
+        Some(Ok("\"Apple\"".to_string()))
+    );
+    assert_eq!(
+        format(meta, &Fruit::Plum(1)),
+        Some(Ok("\"other\"".to_string()))
+    );
+    let meta = Fruit::Apple.assoc_meta("from_weight").unwrap();
+    assert_eq!(format(meta, &Fruit::Apple), None);
+    assert!(Fruit::Plum(1).assoc_meta("label").is_none());
+    assert!(Fruit::Apple.assoc_meta("missing").is_none());
+    assert_eq!(Fruit::from_weight(150), Some(Fruit::Apple));
+    assert_eq!(Fruit::Plum(7).weight(), Some(7));
+    assert_eq!(Fruit::Apple.scaled(2), 2);
+    let Opaque = Fruit::Apple.opaque();
+}